@@ -0,0 +1,379 @@
+use crate::client::archive::query::ArchiveQuery;
+use crate::client::archive::store::{ArchiveEntry, ArchiveRecord, InvoiceArchive};
+use crate::client::error::{IntegrityCheck, KsefError};
+use crate::client::fetching_invoices::fetch_invoice::compute_invoice_hash;
+use crate::client::fs::write_atomically;
+use crate::client::types::KsefNumber;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+const INDEX_FILE: &str = "index.jsonl";
+const INVOICES_DIR: &str = "invoices";
+
+type ArchiveIndex = BTreeMap<String, ArchiveEntry>;
+
+#[derive(Debug)]
+pub struct FileArchive {
+    root: PathBuf,
+    index: Mutex<Option<ArchiveIndex>>,
+}
+
+impl FileArchive {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            index: Mutex::new(None),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn invoice_path(&self, ksef_number: &KsefNumber, suffix: &str) -> Result<PathBuf, KsefError> {
+        if ksef_number.is_empty()
+            || ksef_number.contains(['/', '\\'])
+            || ksef_number.starts_with('.')
+        {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!("Invalid KSeF number for a file name: {}", ksef_number),
+            ));
+        }
+        Ok(self
+            .root
+            .join(INVOICES_DIR)
+            .join(format!("{}{}", ksef_number, suffix)))
+    }
+
+    async fn load_index(&self) -> Result<ArchiveIndex, KsefError> {
+        let path = self.root.join(INDEX_FILE);
+        if !tokio::fs::try_exists(&path).await? {
+            return Ok(ArchiveIndex::new());
+        }
+        let content = tokio::fs::read(&path).await?;
+
+        let mut index = ArchiveIndex::new();
+        let mut records = 0;
+        let mut lines = content.split(|b| *b == b'\n').peekable();
+        while let Some(line) = lines.next() {
+            if line.is_empty() {
+                continue;
+            }
+            let entry: ArchiveEntry = match serde_json::from_slice(line) {
+                Ok(entry) => entry,
+                // A crash while appending can only leave the last line incomplete.
+                Err(_) if lines.peek().is_none() => break,
+                Err(e) => return Err(e.into()),
+            };
+            records += 1;
+            index.insert(entry.ksef_number.to_string(), entry);
+        }
+
+        if records > index.len() || !content.ends_with(b"\n") {
+            let mut compacted = Vec::new();
+            for entry in index.values() {
+                serde_json::to_writer(&mut compacted, entry)?;
+                compacted.push(b'\n');
+            }
+            write_atomically(&path, &compacted).await?;
+        }
+
+        Ok(index)
+    }
+
+    async fn append_to_index(&self, entry: &ArchiveEntry) -> Result<(), KsefError> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        tokio::fs::create_dir_all(&self.root).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.root.join(INDEX_FILE))
+            .await?;
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    async fn with_index<T, F>(&self, f: F) -> Result<T, KsefError>
+    where
+        F: FnOnce(&ArchiveIndex) -> T + Send,
+    {
+        let mut guard = self.index.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_index().await?);
+        }
+        Ok(f(guard.as_ref().expect("index loaded")))
+    }
+}
+
+#[async_trait]
+impl InvoiceArchive for FileArchive {
+    async fn store(&self, record: ArchiveRecord) -> Result<ArchiveEntry, KsefError> {
+        record.verify()?;
+
+        let xml_path = self.invoice_path(&record.ksef_number, ".xml")?;
+        let metadata_path = self.invoice_path(&record.ksef_number, ".metadata.json")?;
+        let upo_path = self.invoice_path(&record.ksef_number, ".upo.xml")?;
+
+        let mut guard = self.index.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_index().await?);
+        }
+        let index = guard.as_mut().expect("index loaded");
+
+        write_atomically(&xml_path, &record.content).await?;
+        if let Some(metadata) = &record.metadata {
+            write_atomically(&metadata_path, &serde_json::to_vec_pretty(metadata)?).await?;
+        }
+        if let Some(upo) = &record.upo {
+            write_atomically(&upo_path, upo.as_bytes()).await?;
+        }
+
+        let mut entry = ArchiveEntry::from_record(&record);
+        if let Some(existing) = index.get(record.ksef_number.as_str()) {
+            if !entry.has_metadata && existing.has_metadata {
+                entry = ArchiveEntry {
+                    hash: entry.hash,
+                    has_upo: entry.has_upo,
+                    archived_at: entry.archived_at,
+                    ..existing.clone()
+                };
+            }
+            entry.has_upo |= existing.has_upo;
+        }
+
+        self.append_to_index(&entry).await?;
+        index.insert(record.ksef_number.to_string(), entry.clone());
+        Ok(entry)
+    }
+
+    async fn attach_upo(&self, ksef_number: &KsefNumber, upo: &str) -> Result<(), KsefError> {
+        let upo_path = self.invoice_path(ksef_number, ".upo.xml")?;
+
+        let mut guard = self.index.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_index().await?);
+        }
+        let index = guard.as_mut().expect("index loaded");
+
+        let entry = index.get_mut(ksef_number.as_str()).ok_or_else(|| {
+            KsefError::ApplicationError(0, format!("Invoice {} is not in the archive", ksef_number))
+        })?;
+        write_atomically(&upo_path, upo.as_bytes()).await?;
+        let mut updated = entry.clone();
+        updated.has_upo = true;
+        self.append_to_index(&updated).await?;
+        *entry = updated;
+        Ok(())
+    }
+
+    async fn contains(&self, ksef_number: &KsefNumber) -> Result<bool, KsefError> {
+        self.with_index(|index| index.contains_key(ksef_number.as_str()))
+            .await
+    }
+
+    async fn get(&self, ksef_number: &KsefNumber) -> Result<Option<ArchiveRecord>, KsefError> {
+        let Some(entry) = self
+            .with_index(|index| index.get(ksef_number.as_str()).cloned())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let content = tokio::fs::read(self.invoice_path(ksef_number, ".xml")?).await?;
+        let hash = compute_invoice_hash(&content);
+        if hash != entry.hash {
+            return Err(KsefError::integrity(
+                format!("archived invoice {}", ksef_number),
+                IntegrityCheck::Hash,
+                &entry.hash,
+                &hash,
+            ));
+        }
+
+        let metadata = if entry.has_metadata {
+            let json = tokio::fs::read(self.invoice_path(ksef_number, ".metadata.json")?).await?;
+            Some(serde_json::from_slice(&json)?)
+        } else {
+            None
+        };
+        let upo = if entry.has_upo {
+            Some(tokio::fs::read_to_string(self.invoice_path(ksef_number, ".upo.xml")?).await?)
+        } else {
+            None
+        };
+
+        Ok(Some(ArchiveRecord {
+            ksef_number: ksef_number.clone(),
+            content,
+            hash,
+            metadata,
+            upo,
+        }))
+    }
+
+    async fn query(&self, query: &ArchiveQuery) -> Result<Vec<ArchiveEntry>, KsefError> {
+        let mut entries = self
+            .with_index(|index| {
+                index
+                    .values()
+                    .filter(|entry| query.matches(entry))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .await?;
+        entries.sort_by(|a, b| {
+            a.invoicing_date
+                .cmp(&b.invoicing_date)
+                .then_with(|| a.ksef_number.as_str().cmp(b.ksef_number.as_str()))
+        });
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fetching_invoices::fetch_invoice_metadata::InvoiceMetadata;
+    use crate::client::fetching_invoices::fetch_invoice_metadata::{AmountFilter, AmountType};
+    use chrono::NaiveDate;
+
+    fn metadata(ksef_number: &str, buyer_nip: &str, gross: f64, content: &[u8]) -> InvoiceMetadata {
+        serde_json::from_value(serde_json::json!({
+            "ksefNumber": ksef_number,
+            "invoiceNumber": format!("FA/{}", ksef_number),
+            "issueDate": "2025-06-25",
+            "invoicingDate": "2025-06-26T10:00:00+00:00",
+            "permanentStorageDate": "2025-06-26T10:00:05+00:00",
+            "seller": { "nip": "5265877635" },
+            "buyer": { "identifier": { "type": "Nip", "value": buyer_nip } },
+            "grossAmount": gross,
+            "currency": "PLN",
+            "invoicingMode": "Online",
+            "invoiceType": "Vat",
+            "formCode": { "systemCode": "FA (2)", "schemaVersion": "1-0E", "value": "FA" },
+            "isSelfInvoicing": false,
+            "hasAttachment": false,
+            "invoiceHash": compute_invoice_hash(content).as_str(),
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_file_archive_store_query_and_get() {
+        let root = std::env::temp_dir().join(format!("ksef-archive-{}", std::process::id()));
+        let archive = FileArchive::new(&root);
+
+        let first = b"<Faktura>1</Faktura>".to_vec();
+        let second = b"<Faktura>2</Faktura>".to_vec();
+        archive
+            .store(
+                ArchiveRecord::new("KSEF-1", first.clone()).with_metadata(metadata(
+                    "KSEF-1",
+                    "1111111111",
+                    123.0,
+                    &first,
+                )),
+            )
+            .await
+            .unwrap();
+        archive
+            .store(
+                ArchiveRecord::new("KSEF-2", second.clone()).with_metadata(metadata(
+                    "KSEF-2",
+                    "2222222222",
+                    5000.0,
+                    &second,
+                )),
+            )
+            .await
+            .unwrap();
+
+        let tampered = ArchiveRecord::new("KSEF-3", second.clone()).with_metadata(metadata(
+            "KSEF-3",
+            "2222222222",
+            1.0,
+            &first,
+        ));
+        assert!(matches!(
+            archive.store(tampered).await,
+            Err(KsefError::IntegrityError(_))
+        ));
+
+        archive
+            .attach_upo(&KsefNumber::new("KSEF-2"), "<UPO/>")
+            .await
+            .unwrap();
+
+        let reopened = FileArchive::new(&root);
+        let by_buyer = reopened
+            .query(&ArchiveQuery::new().with_nip("2222222222"))
+            .await
+            .unwrap();
+        assert_eq!(by_buyer.len(), 1);
+        assert!(by_buyer[0].has_upo);
+
+        let by_amount = reopened
+            .query(
+                &ArchiveQuery::new()
+                    .with_seller_nip("5265877635")
+                    .with_currency("pln")
+                    .with_issue_date(
+                        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+                        NaiveDate::from_ymd_opt(2025, 6, 30).unwrap(),
+                    )
+                    .with_amount(AmountFilter {
+                        amount_type: AmountType::Brutto,
+                        from: Some(100.0),
+                        to: Some(1000.0),
+                    }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_amount.len(), 1);
+        assert_eq!(by_amount[0].ksef_number.as_str(), "KSEF-1");
+
+        let record = reopened
+            .get(&KsefNumber::new("KSEF-2"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.content, second);
+        assert_eq!(record.upo.as_deref(), Some("<UPO/>"));
+        assert_eq!(record.metadata.unwrap().ksef_number, "KSEF-2");
+
+        let index_path = root.join(INDEX_FILE);
+        let mut log = tokio::fs::read(&index_path).await.unwrap();
+        assert_eq!(log.iter().filter(|b| **b == b'\n').count(), 2);
+        let first_line = log[..=log.iter().position(|b| *b == b'\n').unwrap()].to_vec();
+        log.extend_from_slice(&first_line);
+        log.extend_from_slice(b"{\"ksefNumber\":\"KSEF-");
+        tokio::fs::write(&index_path, &log).await.unwrap();
+
+        let compacted = FileArchive::new(&root);
+        assert!(
+            compacted
+                .contains(&KsefNumber::new("KSEF-2"))
+                .await
+                .unwrap()
+        );
+        let log = tokio::fs::read(&index_path).await.unwrap();
+        assert_eq!(log.iter().filter(|b| **b == b'\n').count(), 2);
+
+        tokio::fs::write(root.join(INVOICES_DIR).join("KSEF-1.xml"), b"<Faktura/>")
+            .await
+            .unwrap();
+        assert!(matches!(
+            reopened.get(&KsefNumber::new("KSEF-1")).await,
+            Err(KsefError::IntegrityError(_))
+        ));
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -0,0 +1,3 @@
+pub mod file_archive;
+pub mod query;
+pub mod store;
@@ -0,0 +1,129 @@
+use crate::client::archive::store::ArchiveEntry;
+use crate::client::fetching_invoices::fetch_invoice_metadata::{AmountFilter, AmountType};
+use chrono::{DateTime, NaiveDate, Utc};
+
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveQuery {
+    pub ksef_number: Option<String>,
+    pub nip: Option<String>,
+    pub seller_nip: Option<String>,
+    pub buyer_nip: Option<String>,
+    pub issue_date_from: Option<NaiveDate>,
+    pub issue_date_to: Option<NaiveDate>,
+    pub invoicing_date_from: Option<DateTime<Utc>>,
+    pub invoicing_date_to: Option<DateTime<Utc>>,
+    pub amount: Option<AmountFilter>,
+    pub currency: Option<String>,
+}
+
+impl ArchiveQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ksef_number(mut self, ksef_number: impl Into<String>) -> Self {
+        self.ksef_number = Some(ksef_number.into());
+        self
+    }
+
+    pub fn with_nip(mut self, nip: impl Into<String>) -> Self {
+        self.nip = Some(nip.into());
+        self
+    }
+
+    pub fn with_seller_nip(mut self, nip: impl Into<String>) -> Self {
+        self.seller_nip = Some(nip.into());
+        self
+    }
+
+    pub fn with_buyer_nip(mut self, nip: impl Into<String>) -> Self {
+        self.buyer_nip = Some(nip.into());
+        self
+    }
+
+    pub fn with_issue_date(mut self, from: NaiveDate, to: NaiveDate) -> Self {
+        self.issue_date_from = Some(from);
+        self.issue_date_to = Some(to);
+        self
+    }
+
+    pub fn with_invoicing_date(mut self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.invoicing_date_from = Some(from);
+        self.invoicing_date_to = Some(to);
+        self
+    }
+
+    pub fn with_amount(mut self, amount: AmountFilter) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = Some(currency.into());
+        self
+    }
+
+    pub fn matches(&self, entry: &ArchiveEntry) -> bool {
+        if let Some(ksef_number) = &self.ksef_number
+            && entry.ksef_number.as_str() != ksef_number
+        {
+            return false;
+        }
+
+        if let Some(nip) = &self.nip
+            && entry.seller_nip.as_ref() != Some(nip)
+            && entry.buyer_nip.as_ref() != Some(nip)
+        {
+            return false;
+        }
+
+        if !matches_value(&self.seller_nip, &entry.seller_nip)
+            || !matches_value(&self.buyer_nip, &entry.buyer_nip)
+            || !matches_value(&self.currency, &entry.currency)
+        {
+            return false;
+        }
+
+        if !in_range(entry.issue_date, self.issue_date_from, self.issue_date_to)
+            || !in_range(
+                entry.invoicing_date,
+                self.invoicing_date_from,
+                self.invoicing_date_to,
+            )
+        {
+            return false;
+        }
+
+        if let Some(filter) = &self.amount {
+            let amount = match filter.amount_type {
+                AmountType::Brutto => entry.gross_amount,
+                AmountType::Netto => entry.net_amount,
+                AmountType::Vat => entry.vat_amount,
+            };
+            if !in_range(amount, filter.from, filter.to) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn matches_value(expected: &Option<String>, actual: &Option<String>) -> bool {
+    match expected {
+        Some(expected) => actual
+            .as_ref()
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+        None => true,
+    }
+}
+
+fn in_range<T: PartialOrd>(value: Option<T>, from: Option<T>, to: Option<T>) -> bool {
+    if from.is_none() && to.is_none() {
+        return true;
+    }
+    let Some(value) = value else {
+        return false;
+    };
+    from.is_none_or(|from| value >= from) && to.is_none_or(|to| value <= to)
+}
@@ -0,0 +1,180 @@
+use crate::client::archive::query::ArchiveQuery;
+use crate::client::error::{IntegrityCheck, KsefError};
+use crate::client::fetching_invoices::fetch_invoice::compute_invoice_hash;
+use crate::client::fetching_invoices::fetch_invoice_metadata::{
+    BuyerIdentifierType, InvoiceMetadata,
+};
+use crate::client::fetching_invoices::incremental_fetch::FetchedInvoice;
+use crate::client::types::{InvoiceHash, KsefNumber};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct ArchiveRecord {
+    pub ksef_number: KsefNumber,
+    pub content: Vec<u8>,
+    pub hash: InvoiceHash,
+    pub metadata: Option<InvoiceMetadata>,
+    pub upo: Option<String>,
+}
+
+impl ArchiveRecord {
+    pub fn new(ksef_number: impl Into<KsefNumber>, content: Vec<u8>) -> Self {
+        let hash = compute_invoice_hash(&content);
+        Self {
+            ksef_number: ksef_number.into(),
+            content,
+            hash,
+            metadata: None,
+            upo: None,
+        }
+    }
+
+    pub fn with_hash(
+        ksef_number: impl Into<KsefNumber>,
+        content: Vec<u8>,
+        hash: impl Into<InvoiceHash>,
+    ) -> Self {
+        Self {
+            ksef_number: ksef_number.into(),
+            content,
+            hash: hash.into(),
+            metadata: None,
+            upo: None,
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: InvoiceMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    pub fn with_upo(mut self, upo: impl Into<String>) -> Self {
+        self.upo = Some(upo.into());
+        self
+    }
+
+    pub fn verify(&self) -> Result<(), KsefError> {
+        let hash = compute_invoice_hash(&self.content);
+        if hash != self.hash {
+            return Err(KsefError::integrity(
+                format!("archived invoice {}", self.ksef_number),
+                IntegrityCheck::Hash,
+                &self.hash,
+                &hash,
+            ));
+        }
+
+        if let Some(metadata) = &self.metadata
+            && !metadata.invoice_hash.is_empty()
+            && metadata.invoice_hash != hash.as_str()
+        {
+            return Err(KsefError::integrity(
+                format!("archived invoice {} (metadata)", self.ksef_number),
+                IntegrityCheck::Hash,
+                &metadata.invoice_hash,
+                &hash,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<FetchedInvoice> for ArchiveRecord {
+    fn from(invoice: FetchedInvoice) -> Self {
+        ArchiveRecord::new(
+            invoice.metadata.ksef_number.as_str(),
+            invoice.content.into_bytes(),
+        )
+        .with_metadata(invoice.metadata)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub ksef_number: KsefNumber,
+    pub hash: InvoiceHash,
+    pub invoice_number: Option<String>,
+    pub seller_nip: Option<String>,
+    pub buyer_nip: Option<String>,
+    pub issue_date: Option<NaiveDate>,
+    pub invoicing_date: Option<DateTime<Utc>>,
+    pub permanent_storage_date: Option<DateTime<Utc>>,
+    pub net_amount: Option<f64>,
+    pub gross_amount: Option<f64>,
+    pub vat_amount: Option<f64>,
+    pub currency: Option<String>,
+    pub has_metadata: bool,
+    pub has_upo: bool,
+    pub archived_at: DateTime<Utc>,
+}
+
+impl ArchiveEntry {
+    pub fn from_record(record: &ArchiveRecord) -> Self {
+        let mut entry = Self {
+            ksef_number: record.ksef_number.clone(),
+            hash: record.hash.clone(),
+            invoice_number: None,
+            seller_nip: None,
+            buyer_nip: None,
+            issue_date: None,
+            invoicing_date: None,
+            permanent_storage_date: None,
+            net_amount: None,
+            gross_amount: None,
+            vat_amount: None,
+            currency: None,
+            has_metadata: false,
+            has_upo: record.upo.is_some(),
+            archived_at: Utc::now(),
+        };
+        if let Some(metadata) = &record.metadata {
+            entry.apply_metadata(metadata);
+        }
+        entry
+    }
+
+    fn apply_metadata(&mut self, metadata: &InvoiceMetadata) {
+        self.invoice_number = Some(metadata.invoice_number.clone());
+        self.seller_nip = Some(metadata.seller.nip.clone());
+        self.buyer_nip = match metadata.buyer.identifier.identifier_type {
+            BuyerIdentifierType::Nip => metadata.buyer.identifier.value.clone(),
+            _ => None,
+        };
+        self.issue_date = Some(metadata.issue_date);
+        self.invoicing_date = Some(metadata.invoicing_date);
+        self.permanent_storage_date = metadata.permanent_storage_date;
+        self.net_amount = metadata.net_amount;
+        self.gross_amount = metadata.gross_amount;
+        self.vat_amount = metadata.vat_amount;
+        self.currency = Some(metadata.currency.clone());
+        self.has_metadata = true;
+    }
+}
+
+#[async_trait]
+pub trait InvoiceArchive: Send + Sync {
+    async fn store(&self, record: ArchiveRecord) -> Result<ArchiveEntry, KsefError>;
+
+    async fn attach_upo(&self, ksef_number: &KsefNumber, upo: &str) -> Result<(), KsefError>;
+
+    async fn contains(&self, ksef_number: &KsefNumber) -> Result<bool, KsefError>;
+
+    async fn get(&self, ksef_number: &KsefNumber) -> Result<Option<ArchiveRecord>, KsefError>;
+
+    async fn query(&self, query: &ArchiveQuery) -> Result<Vec<ArchiveEntry>, KsefError>;
+
+    async fn store_fetched(
+        &self,
+        invoices: Vec<FetchedInvoice>,
+    ) -> Result<Vec<ArchiveEntry>, KsefError> {
+        let mut entries = Vec::with_capacity(invoices.len());
+        for invoice in invoices {
+            entries.push(self.store(ArchiveRecord::from(invoice)).await?);
+        }
+        Ok(entries)
+    }
+}
@@ -1,19 +1,20 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
 #[derive(Debug, Clone)]
 pub struct AuthChallenge {
     pub challenge: String,
-    pub timestamp: String,
+    pub timestamp: DateTime<Utc>,
     pub timestamp_ms: i64,
 }
 
 #[derive(Deserialize)]
 struct AuthChallengeResponse {
     pub challenge: String,
-    pub timestamp: String,
+    pub timestamp: DateTime<Utc>,
     #[serde(rename = "timestampMs")]
     pub timestamp_ms: i64,
 }
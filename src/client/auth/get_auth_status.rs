@@ -2,6 +2,7 @@ use crate::client::traits::*;
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use crate::client::status_codes::AuthStatusCode;
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -11,7 +12,7 @@ struct StatusResponse {
 
 #[derive(Deserialize)]
 struct StatusObject {
-    code: i32,
+    code: AuthStatusCode,
     description: String,
 }
 
@@ -50,11 +51,11 @@ pub async fn get_auth_status(client: &mut KsefClient) -> Result<bool, KsefError>
         let parsed: StatusResponse = resp.json().await?;
 
         match parsed.status.code {
-            100 => {
+            AuthStatusCode::InProgress => {
                 tokio::time::sleep(std::time::Duration::from_secs(1)).await;
                 continue;
             }
-            200 => match client.get_access_token().await {
+            AuthStatusCode::Succeeded => match client.get_access_token().await {
                 Ok(()) => return Ok(true),
                 Err(e) => {
                     return Err(KsefError::Unexpected(format!(
@@ -65,7 +66,7 @@ pub async fn get_auth_status(client: &mut KsefClient) -> Result<bool, KsefError>
             },
             _ => {
                 return Err(KsefError::ApplicationError(
-                    parsed.status.code,
+                    parsed.status.code.code(),
                     parsed.status.description,
                 ));
             }
@@ -1,12 +1,19 @@
 use crate::client::KsefClient;
 use crate::client::batch_session::close_batch_session::close_batch_session;
 use crate::client::batch_session::open_batch_session::{
-    OpenBatchSessionRequestBuilder, open_batch_session,
+    OpenBatchSessionRequest, OpenBatchSessionRequestBuilder, open_batch_session,
 };
 use crate::client::batch_session::upload_batch_parts::upload_batch_parts;
-use crate::client::batch_session::zip::{create_zip, encrypt_zip_parts, split_zip};
+use crate::client::batch_session::zip::{
+    BatchZipResult, EncryptedBatchPart, FileMetadata, create_zip, encrypt_zip_parts, split_zip,
+};
 use crate::client::error::KsefError;
-use crate::client::online_session::encryption::generate_encryption_data;
+use crate::client::form_codes::InvoiceSchema;
+use crate::client::online_session::encryption::{
+    EncryptionData, encrypted_size, generate_encryption_data,
+};
+use crate::client::online_session::send_invoice::SendInvoiceOptions;
+use crate::client::preflight::{KsefLimits, check_batch, check_batch_plan};
 use crate::invoices::InvoicePayload;
 
 #[derive(Debug, Clone)]
@@ -21,11 +28,23 @@ pub async fn submit_batch(
     invoices: &[InvoicePayload],
     max_part_size_bytes: Option<usize>,
 ) -> Result<BatchSubmissionResult, KsefError> {
-    let zip_result = create_zip(invoices)?;
-    let total_size = zip_result.metadata.size;
+    submit_batch_with_options(
+        client,
+        invoices,
+        max_part_size_bytes,
+        &SendInvoiceOptions::default(),
+    )
+    .await
+}
 
-    let max_size = max_part_size_bytes.unwrap_or(50 * 1024 * 1024);
-    let raw_parts = split_zip(&zip_result.content, max_size);
+pub async fn submit_batch_with_options(
+    client: &KsefClient,
+    invoices: &[InvoicePayload],
+    max_part_size_bytes: Option<usize>,
+    options: &SendInvoiceOptions,
+) -> Result<BatchSubmissionResult, KsefError> {
+    let (schema, zip_result, raw_parts) = plan_batch(invoices, max_part_size_bytes, options)?;
+    let total_size = zip_result.metadata.size;
 
     let encryption_data = generate_encryption_data(client).await?;
 
@@ -35,19 +54,13 @@ pub async fn submit_batch(
         &encryption_data.initialization_vector,
     )?;
 
-    let mut builder = OpenBatchSessionRequestBuilder::new()
-        .with_batch_file_info(zip_result.metadata.size, &zip_result.metadata.hash)
-        .with_encryption(
-            &encryption_data.encrypted_symmetric_key,
-            &encryption_data.initialization_vector,
-        );
-
-    for part in encrypted_parts.iter() {
-        builder =
-            builder.add_file_part(part.ordinal_number, part.metadata.size, &part.metadata.hash);
-    }
-
-    let open_request = builder.build()?;
+    let open_request = build_open_batch_request(
+        schema,
+        &zip_result.metadata,
+        &encrypted_parts,
+        &encryption_data,
+        options,
+    )?;
 
     let session_response = open_batch_session(client, open_request).await?;
     let reference_number = session_response.reference_number.clone();
@@ -62,3 +75,61 @@ pub async fn submit_batch(
         total_size_bytes: total_size,
     })
 }
+
+pub(crate) fn plan_batch(
+    invoices: &[InvoicePayload],
+    max_part_size_bytes: Option<usize>,
+    options: &SendInvoiceOptions,
+) -> Result<(InvoiceSchema, BatchZipResult, Vec<Vec<u8>>), KsefError> {
+    options.validate()?;
+    if options.hash_of_corrected_invoice.is_some() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "hash_of_corrected_invoice is not supported in batch sessions".to_string(),
+        ));
+    }
+
+    let limits = KsefLimits::default();
+    if !options.skip_preflight {
+        check_batch(invoices, &limits).into_result()?;
+    }
+
+    let contents: Vec<&[u8]> = invoices.iter().map(|i| i.content.as_slice()).collect();
+    let schema = InvoiceSchema::detect_common(&contents)?;
+
+    let zip_result = create_zip(invoices)?;
+
+    let max_size = max_part_size_bytes.unwrap_or(50 * 1024 * 1024);
+    let raw_parts = split_zip(&zip_result.content, max_size);
+
+    if !options.skip_preflight {
+        let part_sizes: Vec<usize> = raw_parts.iter().map(|p| encrypted_size(p.len())).collect();
+        check_batch_plan(zip_result.content.len(), &part_sizes, &limits).into_result()?;
+    }
+
+    Ok((schema, zip_result, raw_parts))
+}
+
+pub(crate) fn build_open_batch_request(
+    schema: InvoiceSchema,
+    zip_metadata: &FileMetadata,
+    encrypted_parts: &[EncryptedBatchPart],
+    encryption_data: &EncryptionData,
+    options: &SendInvoiceOptions,
+) -> Result<OpenBatchSessionRequest, KsefError> {
+    let mut builder = OpenBatchSessionRequestBuilder::new()
+        .with_schema(schema)
+        .with_batch_file_info(zip_metadata.size, &zip_metadata.hash)
+        .with_encryption(
+            &encryption_data.encrypted_symmetric_key,
+            &encryption_data.initialization_vector,
+        )
+        .with_offline_mode(options.offline_mode);
+
+    for part in encrypted_parts.iter() {
+        builder =
+            builder.add_file_part(part.ordinal_number, part.metadata.size, &part.metadata.hash);
+    }
+
+    builder.build()
+}
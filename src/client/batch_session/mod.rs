@@ -1,5 +1,7 @@
 pub mod close_batch_session;
 pub mod full_flow;
 pub mod open_batch_session;
+pub mod outcome;
+pub mod streaming_batch;
 pub mod upload_batch_parts;
 pub mod zip;
@@ -1,12 +1,13 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
 use crate::client::models::{Encryption, FormCode};
 use crate::client::routes;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenBatchSessionRequest {
     #[serde(rename = "formCode")]
     pub form_code: FormCode,
@@ -14,9 +15,11 @@ pub struct OpenBatchSessionRequest {
     pub batch_file: BatchFile,
     #[serde(rename = "encryption")]
     pub encryption: Encryption,
+    #[serde(rename = "offlineMode")]
+    pub offline_mode: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchFile {
     #[serde(rename = "fileName")]
     pub file_name: String,
@@ -28,7 +31,7 @@ pub struct BatchFile {
     pub part_files: Vec<BatchFilePartInfo>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchFilePartInfo {
     #[serde(rename = "ordinalNumber")]
     pub ordinal_number: usize,
@@ -40,7 +43,7 @@ pub struct BatchFilePartInfo {
     pub file_hash: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenBatchSessionResponse {
     #[serde(rename = "referenceNumber")]
     pub reference_number: String,
@@ -48,7 +51,7 @@ pub struct OpenBatchSessionResponse {
     pub part_upload_requests: Vec<PartUploadRequest>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PartUploadRequest {
     #[serde(rename = "ordinalNumber")]
     pub ordinal_number: usize,
@@ -69,19 +72,21 @@ pub struct OpenBatchSessionRequestBuilder {
     file_parts: Vec<BatchFilePartInfo>,
     encrypted_symmetric_key: Option<Vec<u8>>,
     initialization_vector: Option<Vec<u8>>,
+    offline_mode: bool,
 }
 
 impl OpenBatchSessionRequestBuilder {
     pub fn new() -> Self {
         Self {
-            system_code: Some("FA (2)".to_string()),
-            schema_version: Some("1-0E".to_string()),
-            value: Some("FA".to_string()),
+            system_code: Some(InvoiceSchema::Fa2.system_code().to_string()),
+            schema_version: Some(InvoiceSchema::Fa2.schema_version().to_string()),
+            value: Some(InvoiceSchema::Fa2.value().to_string()),
             batch_file_size: None,
             batch_file_hash: None,
             file_parts: Vec::new(),
             encrypted_symmetric_key: None,
             initialization_vector: None,
+            offline_mode: false,
         }
     }
 
@@ -100,6 +105,17 @@ impl OpenBatchSessionRequestBuilder {
         self
     }
 
+    pub fn with_form_code(mut self, form_code: FormCode) -> Self {
+        self.system_code = Some(form_code.system_code);
+        self.schema_version = Some(form_code.schema_version);
+        self.value = Some(form_code.value);
+        self
+    }
+
+    pub fn with_schema(self, schema: InvoiceSchema) -> Self {
+        self.with_form_code(schema.form_code())
+    }
+
     pub fn with_batch_file_info(mut self, size: usize, hash: &[u8]) -> Self {
         use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
         self.batch_file_size = Some(size);
@@ -124,6 +140,11 @@ impl OpenBatchSessionRequestBuilder {
         self
     }
 
+    pub fn with_offline_mode(mut self, offline_mode: bool) -> Self {
+        self.offline_mode = offline_mode;
+        self
+    }
+
     pub fn build(self) -> Result<OpenBatchSessionRequest, KsefError> {
         use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
@@ -172,6 +193,7 @@ impl OpenBatchSessionRequestBuilder {
                 encrypted_symmetric_key: BASE64.encode(&key),
                 initialization_vector: BASE64.encode(&iv),
             },
+            offline_mode: self.offline_mode,
         })
     }
 }
@@ -250,6 +272,7 @@ mod tests {
                 encrypted_symmetric_key: "key".to_string(),
                 initialization_vector: "iv".to_string(),
             },
+            offline_mode: true,
         };
         let json = serde_json::to_string(&req).expect("Failed to serialize");
         println!("{}", json);
@@ -258,5 +281,6 @@ mod tests {
         assert!(json.contains("\"fileName\":\"part-1.zip.aes\""));
         assert!(json.contains("\"fileSize\":50"));
         assert!(json.contains("\"fileHash\":\"part_hash\""));
+        assert!(json.contains("\"offlineMode\":true"));
     }
 }
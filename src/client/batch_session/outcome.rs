@@ -0,0 +1,379 @@
+use crate::client::KsefClient;
+use crate::client::batch_session::zip::calculate_invoice_hash;
+use crate::client::error::KsefError;
+use crate::client::fs::write_atomically;
+use crate::client::models::InvoicePayload;
+use crate::client::online_session::get_invoice_status::GetInvoiceStatusResponse;
+use crate::client::sessions::get_session_invoices::{
+    get_failed_session_invoices, get_session_invoices,
+};
+use crate::client::sessions::get_session_status::get_session_status;
+use crate::client::status_codes::{InvoiceStatusCode, SessionStatusCode};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+const CSV_HEADER: &str = "filename,invoiceHash,ordinalNumber,referenceNumber,ksefNumber,statusCode,statusDescription,details";
+
+#[derive(Debug, Clone)]
+pub struct BatchOutcomeOptions {
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub page_size: i32,
+}
+
+impl Default for BatchOutcomeOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(600),
+            page_size: 100,
+        }
+    }
+}
+
+impl BatchOutcomeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_page_size(mut self, page_size: i32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchInvoiceOutcome {
+    pub filename: Option<String>,
+    pub invoice_hash: String,
+    pub ordinal_number: Option<i32>,
+    pub reference_number: Option<String>,
+    pub ksef_number: Option<String>,
+    pub status_code: Option<InvoiceStatusCode>,
+    pub status_description: Option<String>,
+    pub details: Vec<String>,
+}
+
+impl BatchInvoiceOutcome {
+    pub fn is_accepted(&self) -> bool {
+        self.status_code.is_some_and(|c| c.is_success())
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status_code.is_some_and(|c| c.is_error())
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.status_code.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOutcome {
+    pub reference_number: String,
+    pub session_status: SessionStatusCode,
+    pub session_status_description: String,
+    pub invoice_count: Option<i32>,
+    pub successful_invoice_count: Option<i32>,
+    pub failed_invoice_count: Option<i32>,
+    pub invoices: Vec<BatchInvoiceOutcome>,
+}
+
+impl BatchOutcome {
+    pub fn accepted(&self) -> impl Iterator<Item = &BatchInvoiceOutcome> {
+        self.invoices.iter().filter(|i| i.is_accepted())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &BatchInvoiceOutcome> {
+        self.invoices.iter().filter(|i| i.is_failed())
+    }
+
+    pub fn missing(&self) -> impl Iterator<Item = &BatchInvoiceOutcome> {
+        self.invoices.iter().filter(|i| i.is_missing())
+    }
+
+    pub fn is_fully_accepted(&self) -> bool {
+        self.session_status.is_success() && self.invoices.iter().all(|i| i.is_accepted())
+    }
+
+    pub fn to_json(&self) -> Result<String, KsefError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        for invoice in &self.invoices {
+            let fields = [
+                invoice.filename.clone().unwrap_or_default(),
+                invoice.invoice_hash.clone(),
+                invoice
+                    .ordinal_number
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                invoice.reference_number.clone().unwrap_or_default(),
+                invoice.ksef_number.clone().unwrap_or_default(),
+                invoice
+                    .status_code
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+                invoice.status_description.clone().unwrap_or_default(),
+                invoice.details.join("; "),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub async fn write_json(&self, path: &Path) -> Result<(), KsefError> {
+        write_atomically(path, self.to_json()?.as_bytes()).await
+    }
+
+    pub async fn write_csv(&self, path: &Path) -> Result<(), KsefError> {
+        write_atomically(path, self.to_csv().as_bytes()).await
+    }
+}
+
+pub async fn get_batch_outcome(
+    client: &KsefClient,
+    reference_number: &str,
+    invoices: &[InvoicePayload],
+    options: &BatchOutcomeOptions,
+) -> Result<BatchOutcome, KsefError> {
+    let started = Instant::now();
+    let status = loop {
+        let status = get_session_status(client, reference_number).await?;
+        if !status.status.code.is_in_progress() {
+            break status;
+        }
+        if started.elapsed() >= options.timeout {
+            return Err(KsefError::RuntimeError(
+                "Batch session processing timed out".to_string(),
+            ));
+        }
+        sleep(options.interval).await;
+    };
+
+    let mut statuses = Vec::new();
+    let mut seen = HashSet::new();
+    for failed_only in [false, true] {
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let page = if failed_only {
+                get_failed_session_invoices(
+                    client,
+                    reference_number,
+                    Some(options.page_size),
+                    continuation_token.as_deref(),
+                )
+                .await?
+            } else {
+                get_session_invoices(
+                    client,
+                    reference_number,
+                    Some(options.page_size),
+                    continuation_token.as_deref(),
+                )
+                .await?
+            };
+
+            for invoice in page.invoices {
+                if seen.insert(invoice.reference_number.clone()) {
+                    statuses.push(invoice);
+                }
+            }
+
+            continuation_token = page.continuation_token.filter(|t| !t.is_empty());
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(BatchOutcome {
+        reference_number: reference_number.to_string(),
+        session_status: status.status.code,
+        session_status_description: status.status.description,
+        invoice_count: status.invoice_count,
+        successful_invoice_count: status.successful_invoice_count,
+        failed_invoice_count: status.failed_invoice_count,
+        invoices: reconcile(invoices, statuses),
+    })
+}
+
+fn reconcile(
+    invoices: &[InvoicePayload],
+    statuses: Vec<GetInvoiceStatusResponse>,
+) -> Vec<BatchInvoiceOutcome> {
+    let mut by_filename: HashMap<String, usize> = HashMap::new();
+    let mut by_hash: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut outcomes: Vec<BatchInvoiceOutcome> = Vec::with_capacity(invoices.len());
+
+    for (index, invoice) in invoices.iter().enumerate() {
+        let hash = BASE64.encode(calculate_invoice_hash(&invoice.content));
+        by_filename.insert(invoice.filename.clone(), index);
+        by_hash.entry(hash.clone()).or_default().push(index);
+        outcomes.push(BatchInvoiceOutcome {
+            filename: Some(invoice.filename.clone()),
+            invoice_hash: hash,
+            ordinal_number: None,
+            reference_number: None,
+            ksef_number: None,
+            status_code: None,
+            status_description: None,
+            details: Vec::new(),
+        });
+    }
+
+    let mut unmatched = Vec::new();
+    for status in statuses {
+        let by_name = status
+            .invoice_file_name
+            .as_ref()
+            .and_then(|name| by_filename.get(name).copied())
+            .filter(|i| outcomes[*i].status_code.is_none());
+        let index = by_name.or_else(|| {
+            by_hash.get(&status.invoice_hash).and_then(|indices| {
+                indices
+                    .iter()
+                    .copied()
+                    .find(|i| outcomes[*i].status_code.is_none())
+            })
+        });
+
+        match index {
+            Some(index) => apply_status(&mut outcomes[index], status),
+            None => {
+                let mut outcome = BatchInvoiceOutcome {
+                    filename: status.invoice_file_name.clone(),
+                    invoice_hash: status.invoice_hash.clone(),
+                    ordinal_number: None,
+                    reference_number: None,
+                    ksef_number: None,
+                    status_code: None,
+                    status_description: None,
+                    details: Vec::new(),
+                };
+                apply_status(&mut outcome, status);
+                unmatched.push(outcome);
+            }
+        }
+    }
+
+    outcomes.extend(unmatched);
+    outcomes
+}
+
+fn apply_status(outcome: &mut BatchInvoiceOutcome, status: GetInvoiceStatusResponse) {
+    outcome.ordinal_number = Some(status.ordinal_number);
+    outcome.reference_number = Some(status.reference_number);
+    outcome.ksef_number = status.ksef_number;
+    outcome.status_code = Some(status.invoice_status.code);
+    outcome.status_description = Some(status.invoice_status.description);
+    outcome.details = status.invoice_status.details.unwrap_or_default();
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(filename: &str, content: &str) -> InvoicePayload {
+        InvoicePayload {
+            filename: filename.to_string(),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    fn status(
+        ordinal_number: i32,
+        filename: Option<&str>,
+        content: &str,
+        code: i32,
+    ) -> GetInvoiceStatusResponse {
+        serde_json::from_value(serde_json::json!({
+            "ordinalNumber": ordinal_number,
+            "ksefNumber": if code == 200 { Some(format!("KSEF-{}", ordinal_number)) } else { None },
+            "referenceNumber": format!("REF-{}", ordinal_number),
+            "invoiceHash": BASE64.encode(calculate_invoice_hash(content.as_bytes())),
+            "invoiceFileName": filename,
+            "invoicingDate": "2025-01-01T00:00:00Z",
+            "status": {
+                "code": code,
+                "description": if code == 200 { "Sukces" } else { "Błąd, weryfikacji" },
+                "details": if code == 200 { None } else { Some(vec!["Niepoprawny \"NIP\""]) },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reconcile_joins_by_filename_and_hash() {
+        let invoices = vec![
+            payload("a.xml", "<a/>"),
+            payload("b.xml", "<b/>"),
+            payload("c.xml", "<c/>"),
+        ];
+        let statuses = vec![
+            status(1, Some("a.xml"), "<a/>", 200),
+            status(2, None, "<b/>", 450),
+        ];
+
+        let outcome = BatchOutcome {
+            reference_number: "REF".to_string(),
+            session_status: SessionStatusCode::Processed,
+            session_status_description: String::new(),
+            invoice_count: Some(3),
+            successful_invoice_count: Some(1),
+            failed_invoice_count: Some(1),
+            invoices: reconcile(&invoices, statuses),
+        };
+
+        assert_eq!(outcome.accepted().count(), 1);
+        assert_eq!(outcome.invoices[0].ksef_number.as_deref(), Some("KSEF-1"));
+        assert_eq!(
+            outcome.failed().next().unwrap().filename.as_deref(),
+            Some("b.xml")
+        );
+        assert_eq!(
+            outcome.missing().next().unwrap().filename.as_deref(),
+            Some("c.xml")
+        );
+        assert!(!outcome.is_fully_accepted());
+
+        let csv = outcome.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[2].ends_with(",450,\"Błąd, weryfikacji\",\"Niepoprawny \"\"NIP\"\"\""));
+
+        let json: serde_json::Value = serde_json::from_str(&outcome.to_json().unwrap()).unwrap();
+        assert_eq!(json["invoices"][0]["statusCode"], 200);
+        assert_eq!(json["sessionStatus"], 200);
+    }
+}
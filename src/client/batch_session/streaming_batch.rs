@@ -0,0 +1,557 @@
+use crate::client::KsefClient;
+use crate::client::batch_session::close_batch_session::close_batch_session;
+use crate::client::batch_session::full_flow::BatchSubmissionResult;
+use crate::client::batch_session::open_batch_session::{
+    OpenBatchSessionRequestBuilder, open_batch_session,
+};
+use crate::client::batch_session::upload_batch_parts::upload_batch_part_files;
+use crate::client::batch_session::zip::FileMetadata;
+use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
+use crate::client::models::InvoicePayload;
+use crate::client::online_session::encryption::{EncryptionData, generate_encryption_data};
+use crate::client::online_session::send_invoice::SendInvoiceOptions;
+use crate::client::preflight::{BatchPreflight, KsefLimits, check_batch_plan};
+use openssl::sha::Sha256;
+use openssl::symm::{Cipher, Crypter, Mode};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const DEFAULT_MAX_PART_SIZE: usize = 50 * 1024 * 1024;
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+static WORK_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct StreamingBatchBuilder {
+    max_part_size: usize,
+    work_dir: Option<PathBuf>,
+    limits: KsefLimits,
+    skip_preflight: bool,
+}
+
+#[derive(Debug)]
+pub struct BatchPartFile {
+    pub ordinal_number: usize,
+    pub path: PathBuf,
+    pub metadata: FileMetadata,
+}
+
+#[derive(Debug)]
+pub struct PreparedBatch {
+    pub schema: InvoiceSchema,
+    pub invoice_count: usize,
+    pub metadata: FileMetadata,
+    pub parts: Vec<BatchPartFile>,
+    work_dir: WorkDir,
+}
+
+#[derive(Debug)]
+struct WorkDir {
+    path: PathBuf,
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+impl BatchPartFile {
+    pub fn read(&self) -> Result<Vec<u8>, KsefError> {
+        Ok(fs::read(&self.path)?)
+    }
+}
+
+impl PreparedBatch {
+    pub fn work_dir(&self) -> &Path {
+        &self.work_dir.path
+    }
+}
+
+impl Default for StreamingBatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingBatchBuilder {
+    pub fn new() -> Self {
+        Self {
+            max_part_size: DEFAULT_MAX_PART_SIZE,
+            work_dir: None,
+            limits: KsefLimits::default(),
+            skip_preflight: false,
+        }
+    }
+
+    pub fn with_max_part_size(mut self, max_part_size: usize) -> Self {
+        self.max_part_size = max_part_size;
+        self
+    }
+
+    pub fn with_work_dir(mut self, work_dir: impl Into<PathBuf>) -> Self {
+        self.work_dir = Some(work_dir.into());
+        self
+    }
+
+    pub fn with_limits(mut self, limits: KsefLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_skip_preflight(mut self, skip_preflight: bool) -> Self {
+        self.skip_preflight = skip_preflight;
+        self
+    }
+
+    pub fn build<I>(&self, invoices: I, key: &[u8], iv: &[u8]) -> Result<PreparedBatch, KsefError>
+    where
+        I: IntoIterator<Item = InvoicePayload>,
+    {
+        self.build_from_results(invoices.into_iter().map(Ok), key, iv)
+    }
+
+    pub fn build_from_dir(
+        &self,
+        dir: &Path,
+        key: &[u8],
+        iv: &[u8],
+    ) -> Result<PreparedBatch, KsefError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_xml = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("xml"));
+            if path.is_file() && is_xml {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let invoices = paths.into_iter().map(|path| {
+            let filename = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| {
+                    KsefError::ApplicationError(
+                        0,
+                        format!("Invalid invoice file name: {}", path.display()),
+                    )
+                })?
+                .to_string();
+            let content = fs::read(&path)?;
+            Ok(InvoicePayload { filename, content })
+        });
+
+        self.build_from_results(invoices, key, iv)
+    }
+
+    fn build_from_results<I>(
+        &self,
+        invoices: I,
+        key: &[u8],
+        iv: &[u8],
+    ) -> Result<PreparedBatch, KsefError>
+    where
+        I: Iterator<Item = Result<InvoicePayload, KsefError>>,
+    {
+        if self.max_part_size == 0 {
+            return Err(KsefError::ApplicationError(
+                0,
+                "Maximum part size must be greater than zero".to_string(),
+            ));
+        }
+
+        let work_dir = self.create_work_dir()?;
+        let zip_path = work_dir.path.join("batch.zip");
+
+        let preflight = (!self.skip_preflight).then(|| BatchPreflight::new(self.limits.clone()));
+        let (schema, invoice_count) = write_zip(&zip_path, invoices, preflight)?;
+        let (metadata, parts) =
+            split_and_encrypt(&zip_path, &work_dir.path, self.max_part_size, key, iv)?;
+        fs::remove_file(&zip_path)?;
+
+        if !self.skip_preflight {
+            check_prepared_plan(metadata.size, &parts, &self.limits)?;
+        }
+
+        Ok(PreparedBatch {
+            schema,
+            invoice_count,
+            metadata,
+            parts,
+            work_dir,
+        })
+    }
+
+    fn create_work_dir(&self) -> Result<WorkDir, KsefError> {
+        let base = self.work_dir.clone().unwrap_or_else(std::env::temp_dir);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let path = base.join(format!(
+            "ksef-batch-{}-{}-{}",
+            std::process::id(),
+            nanos,
+            WORK_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path)?;
+        Ok(WorkDir { path })
+    }
+}
+
+fn write_zip<I>(
+    zip_path: &Path,
+    invoices: I,
+    mut preflight: Option<BatchPreflight>,
+) -> Result<(InvoiceSchema, usize), KsefError>
+where
+    I: Iterator<Item = Result<InvoicePayload, KsefError>>,
+{
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(zip_path)?));
+
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    let mut schema: Option<InvoiceSchema> = None;
+    let mut invoice_count = 0;
+
+    for invoice in invoices {
+        let invoice = invoice?;
+
+        if let Some(preflight) = preflight.as_mut() {
+            preflight.add(&invoice);
+            if !preflight.report().is_ok() {
+                return Err(KsefError::PreflightError(Box::new(
+                    preflight.report().clone(),
+                )));
+            }
+        }
+
+        let detected = InvoiceSchema::detect(&invoice.content)?;
+        match schema {
+            None => schema = Some(detected),
+            Some(first) if first != detected => {
+                return Err(KsefError::ApplicationError(
+                    0,
+                    format!(
+                        "Mixed invoice schemas in one session: {} and {} ({})",
+                        first, detected, invoice.filename
+                    ),
+                ));
+            }
+            Some(_) => {}
+        }
+
+        zip.start_file(&invoice.filename, options).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Failed to start file in zip: {}", e))
+        })?;
+        zip.write_all(&invoice.content).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Failed to write file content to zip: {}", e))
+        })?;
+        invoice_count += 1;
+    }
+
+    let mut writer = zip.finish().map_err(|e| {
+        KsefError::ApplicationError(0, format!("Failed to finish zip creation: {}", e))
+    })?;
+    writer.flush()?;
+
+    if let Some(preflight) = preflight {
+        preflight.finish().into_result()?;
+    }
+
+    let schema = schema.ok_or_else(|| {
+        KsefError::ApplicationError(0, "No invoices to put into the batch".to_string())
+    })?;
+
+    Ok((schema, invoice_count))
+}
+
+fn split_and_encrypt(
+    zip_path: &Path,
+    work_dir: &Path,
+    max_part_size: usize,
+    key: &[u8],
+    iv: &[u8],
+) -> Result<(FileMetadata, Vec<BatchPartFile>), KsefError> {
+    let len = fs::metadata(zip_path)?.len() as usize;
+    let part_count = len.div_ceil(max_part_size);
+    let part_size = len.div_ceil(part_count.max(1));
+
+    let mut reader = BufReader::new(File::open(zip_path)?);
+    let mut zip_hasher = Sha256::new();
+    let mut buffer = vec![0u8; STREAM_BUFFER_SIZE];
+    let cipher = Cipher::aes_256_cbc();
+    let mut encrypted = vec![0u8; STREAM_BUFFER_SIZE + cipher.block_size()];
+
+    let mut parts = Vec::with_capacity(part_count);
+    let mut remaining_total = len;
+
+    for ordinal_number in 1..=part_count {
+        let path = work_dir.join(format!("part-{}.zip.aes", ordinal_number));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        let mut crypter = Crypter::new(cipher, Mode::Encrypt, key, Some(iv))?;
+        let mut part_hasher = Sha256::new();
+        let mut part_len = 0;
+
+        let mut remaining = part_size.min(remaining_total);
+        remaining_total -= remaining;
+
+        while remaining > 0 {
+            let chunk = remaining.min(buffer.len());
+            reader.read_exact(&mut buffer[..chunk])?;
+            zip_hasher.update(&buffer[..chunk]);
+
+            let count = crypter.update(&buffer[..chunk], &mut encrypted)?;
+            part_hasher.update(&encrypted[..count]);
+            writer.write_all(&encrypted[..count])?;
+            part_len += count;
+            remaining -= chunk;
+        }
+
+        let count = crypter.finalize(&mut encrypted)?;
+        part_hasher.update(&encrypted[..count]);
+        writer.write_all(&encrypted[..count])?;
+        part_len += count;
+        writer.flush()?;
+
+        parts.push(BatchPartFile {
+            ordinal_number,
+            path,
+            metadata: FileMetadata {
+                hash: part_hasher.finish().to_vec(),
+                size: part_len,
+            },
+        });
+    }
+
+    Ok((
+        FileMetadata {
+            hash: zip_hasher.finish().to_vec(),
+            size: len,
+        },
+        parts,
+    ))
+}
+
+fn check_prepared_plan(
+    zip_size: usize,
+    parts: &[BatchPartFile],
+    limits: &KsefLimits,
+) -> Result<(), KsefError> {
+    let part_sizes: Vec<usize> = parts.iter().map(|p| p.metadata.size).collect();
+    check_batch_plan(zip_size, &part_sizes, limits).into_result()?;
+    Ok(())
+}
+
+pub async fn submit_prepared_batch(
+    client: &KsefClient,
+    prepared: &PreparedBatch,
+    encryption_data: &EncryptionData,
+    options: &SendInvoiceOptions,
+) -> Result<BatchSubmissionResult, KsefError> {
+    options.validate()?;
+    if options.hash_of_corrected_invoice.is_some() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "hash_of_corrected_invoice is not supported in batch sessions".to_string(),
+        ));
+    }
+    if !options.skip_preflight {
+        check_prepared_plan(
+            prepared.metadata.size,
+            &prepared.parts,
+            &KsefLimits::default(),
+        )?;
+    }
+
+    let mut builder = OpenBatchSessionRequestBuilder::new()
+        .with_schema(prepared.schema)
+        .with_batch_file_info(prepared.metadata.size, &prepared.metadata.hash)
+        .with_encryption(
+            &encryption_data.encrypted_symmetric_key,
+            &encryption_data.initialization_vector,
+        )
+        .with_offline_mode(options.offline_mode);
+
+    for part in prepared.parts.iter() {
+        builder =
+            builder.add_file_part(part.ordinal_number, part.metadata.size, &part.metadata.hash);
+    }
+
+    let open_request = builder.build()?;
+
+    let session_response = open_batch_session(client, open_request).await?;
+    let reference_number = session_response.reference_number.clone();
+
+    upload_batch_part_files(client, &session_response, &prepared.parts).await?;
+
+    close_batch_session(client, &reference_number).await?;
+
+    Ok(BatchSubmissionResult {
+        reference_number,
+        number_of_parts: prepared.parts.len(),
+        total_size_bytes: prepared.metadata.size,
+    })
+}
+
+pub async fn submit_batch_from_iter<I>(
+    client: &KsefClient,
+    invoices: I,
+    max_part_size_bytes: Option<usize>,
+) -> Result<BatchSubmissionResult, KsefError>
+where
+    I: IntoIterator<Item = InvoicePayload> + Send + 'static,
+    I::IntoIter: Send,
+{
+    submit_streaming_batch(client, max_part_size_bytes, move |builder, key, iv| {
+        builder.build(invoices, key, iv)
+    })
+    .await
+}
+
+pub async fn submit_batch_from_dir(
+    client: &KsefClient,
+    dir: &Path,
+    max_part_size_bytes: Option<usize>,
+) -> Result<BatchSubmissionResult, KsefError> {
+    let dir = dir.to_path_buf();
+    submit_streaming_batch(client, max_part_size_bytes, move |builder, key, iv| {
+        builder.build_from_dir(&dir, key, iv)
+    })
+    .await
+}
+
+async fn submit_streaming_batch<F>(
+    client: &KsefClient,
+    max_part_size_bytes: Option<usize>,
+    prepare: F,
+) -> Result<BatchSubmissionResult, KsefError>
+where
+    F: FnOnce(&StreamingBatchBuilder, &[u8], &[u8]) -> Result<PreparedBatch, KsefError>
+        + Send
+        + 'static,
+{
+    let encryption_data = generate_encryption_data(client).await?;
+    let builder = StreamingBatchBuilder::new()
+        .with_max_part_size(max_part_size_bytes.unwrap_or(DEFAULT_MAX_PART_SIZE));
+
+    let key = encryption_data.symmetric_key.clone();
+    let iv = encryption_data.initialization_vector.clone();
+    let prepared = tokio::task::spawn_blocking(move || prepare(&builder, &key, &iv))
+        .await
+        .map_err(|e| KsefError::RuntimeError(format!("Batch preparation task failed: {}", e)))??;
+
+    submit_prepared_batch(
+        client,
+        &prepared,
+        &encryption_data,
+        &SendInvoiceOptions::default(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::sha::sha256;
+    use openssl::symm::decrypt;
+    use std::io::Cursor;
+    use zip::ZipArchive;
+
+    fn invoice(index: usize) -> InvoicePayload {
+        let content = format!(
+            r#"<Faktura xmlns="http://crd.gov.pl/wzor/2023/06/29/12648/"><P_2>{}</P_2>{}</Faktura>"#,
+            index,
+            "x".repeat(index * 37 % 500)
+        );
+        InvoicePayload {
+            filename: format!("invoice-{}.xml", index),
+            content: content.into_bytes(),
+        }
+    }
+
+    const KEY: [u8; 32] = [3u8; 32];
+    const IV: [u8; 16] = [7u8; 16];
+
+    #[test]
+    fn test_streaming_batch_roundtrip() {
+        let prepared = StreamingBatchBuilder::new()
+            .with_max_part_size(1024)
+            .build((0..50).map(invoice), &KEY, &IV)
+            .unwrap();
+
+        assert_eq!(prepared.schema, InvoiceSchema::Fa2);
+        assert_eq!(prepared.invoice_count, 50);
+        assert!(prepared.parts.len() > 1);
+
+        let mut zip_content = Vec::new();
+        for (index, part) in prepared.parts.iter().enumerate() {
+            assert_eq!(part.ordinal_number, index + 1);
+            let encrypted = part.read().unwrap();
+            assert_eq!(encrypted.len(), part.metadata.size);
+            assert_eq!(sha256(&encrypted).to_vec(), part.metadata.hash);
+
+            let decrypted = decrypt(Cipher::aes_256_cbc(), &KEY, Some(&IV), &encrypted).unwrap();
+            assert!(decrypted.len() <= 1024);
+            zip_content.extend_from_slice(&decrypted);
+        }
+
+        assert_eq!(zip_content.len(), prepared.metadata.size);
+        assert_eq!(sha256(&zip_content).to_vec(), prepared.metadata.hash);
+
+        let archive = ZipArchive::new(Cursor::new(zip_content)).unwrap();
+        assert_eq!(archive.len(), 50);
+
+        let work_dir = prepared.work_dir().to_path_buf();
+        assert!(work_dir.exists());
+        drop(prepared);
+        assert!(!work_dir.exists());
+    }
+
+    #[test]
+    fn test_streaming_batch_rejects_mixed_schemas() {
+        let fa3 = InvoicePayload {
+            filename: "fa3.xml".to_string(),
+            content: br#"<Faktura xmlns="http://crd.gov.pl/wzor/2025/06/25/13775/"/>"#.to_vec(),
+        };
+
+        let result = StreamingBatchBuilder::new().build(vec![invoice(1), fa3], &KEY, &IV);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_streaming_batch_runs_preflight() {
+        let mut duplicate = invoice(2);
+        duplicate.filename = "invoice-1.xml".to_string();
+        let result = StreamingBatchBuilder::new().build(vec![invoice(1), duplicate], &KEY, &IV);
+        assert!(matches!(result, Err(KsefError::PreflightError(_))));
+
+        let limits = KsefLimits {
+            max_part_size: 512,
+            ..KsefLimits::default()
+        };
+        let builder = StreamingBatchBuilder::new()
+            .with_max_part_size(1024)
+            .with_limits(limits);
+        let result = builder.build((0..50).map(invoice), &KEY, &IV);
+        assert!(matches!(result, Err(KsefError::PreflightError(_))));
+
+        let prepared = builder
+            .with_skip_preflight(true)
+            .build((0..50).map(invoice), &KEY, &IV)
+            .unwrap();
+        assert!(prepared.parts.len() > 1);
+    }
+}
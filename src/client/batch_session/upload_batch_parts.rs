@@ -1,19 +1,217 @@
 use crate::client::KsefClient;
-use crate::client::batch_session::open_batch_session::OpenBatchSessionResponse;
+use crate::client::batch_session::open_batch_session::{
+    OpenBatchSessionResponse, PartUploadRequest,
+};
+use crate::client::batch_session::streaming_batch::BatchPartFile;
 use crate::client::batch_session::zip::EncryptedBatchPart;
 use crate::client::error::KsefError;
-use reqwest::Method;
+use crate::client::fs::write_atomically;
+use crate::client::retry::RetryPolicy;
+use futures::stream::{self, StreamExt};
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::{Body, Method};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use tokio::sync::Mutex;
+use tokio_util::io::ReaderStream;
+
+pub struct PartBody {
+    pub body: Body,
+    pub content_length: u64,
+}
+
+pub trait BatchPart: Sync {
+    fn ordinal_number(&self) -> usize;
+
+    fn body(&self) -> impl Future<Output = Result<PartBody, KsefError>> + Send;
+}
+
+impl BatchPart for EncryptedBatchPart {
+    fn ordinal_number(&self) -> usize {
+        self.ordinal_number
+    }
+
+    async fn body(&self) -> Result<PartBody, KsefError> {
+        Ok(PartBody {
+            body: Body::from(self.content.clone()),
+            content_length: self.content.len() as u64,
+        })
+    }
+}
+
+impl BatchPart for BatchPartFile {
+    fn ordinal_number(&self) -> usize {
+        self.ordinal_number
+    }
+
+    async fn body(&self) -> Result<PartBody, KsefError> {
+        let file = tokio::fs::File::open(&self.path).await?;
+        let content_length = file.metadata().await?.len();
+        Ok(PartBody {
+            body: Body::wrap_stream(ReaderStream::new(file)),
+            content_length,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchUploadOptions {
+    pub concurrency: usize,
+    pub retry_policy: RetryPolicy,
+    pub completed_ordinals: BTreeSet<usize>,
+    pub state_file: Option<PathBuf>,
+}
+
+impl Default for BatchUploadOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+            completed_ordinals: BTreeSet::new(),
+            state_file: None,
+        }
+    }
+}
+
+impl BatchUploadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_completed_ordinals(mut self, ordinals: impl IntoIterator<Item = usize>) -> Self {
+        self.completed_ordinals.extend(ordinals);
+        self
+    }
+
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUploadState {
+    pub reference_number: String,
+    pub completed_ordinals: BTreeSet<usize>,
+}
+
+impl BatchUploadState {
+    pub fn new(reference_number: impl Into<String>) -> Self {
+        Self {
+            reference_number: reference_number.into(),
+            completed_ordinals: BTreeSet::new(),
+        }
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, KsefError> {
+        let content = tokio::fs::read(path).await?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), KsefError> {
+        write_atomically(path, &serde_json::to_vec_pretty(self)?).await
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BatchUploadReport {
+    pub uploaded: BTreeSet<usize>,
+    pub skipped: BTreeSet<usize>,
+    pub failed: BTreeMap<usize, KsefError>,
+    pub state_save_error: Option<KsefError>,
+}
+
+impl BatchUploadReport {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn completed_ordinals(&self) -> BTreeSet<usize> {
+        self.uploaded.union(&self.skipped).copied().collect()
+    }
+
+    pub fn into_result(mut self) -> Result<(), KsefError> {
+        match self.failed.pop_first() {
+            None => Ok(()),
+            Some((_, e)) => Err(e),
+        }
+    }
+}
 
 pub async fn upload_batch_parts(
     client: &KsefClient,
     session_response: &OpenBatchSessionResponse,
     parts: &[EncryptedBatchPart],
 ) -> Result<(), KsefError> {
+    upload_batch_parts_with_options(
+        client,
+        session_response,
+        parts,
+        &BatchUploadOptions::default(),
+    )
+    .await?
+    .into_result()
+}
+
+pub async fn upload_batch_part_files(
+    client: &KsefClient,
+    session_response: &OpenBatchSessionResponse,
+    parts: &[BatchPartFile],
+) -> Result<(), KsefError> {
+    upload_batch_parts_with_options(
+        client,
+        session_response,
+        parts,
+        &BatchUploadOptions::default(),
+    )
+    .await?
+    .into_result()
+}
+
+pub async fn upload_batch_parts_with_options<P: BatchPart>(
+    client: &KsefClient,
+    session_response: &OpenBatchSessionResponse,
+    parts: &[P],
+    options: &BatchUploadOptions,
+) -> Result<BatchUploadReport, KsefError> {
+    let mut state = BatchUploadState::new(&session_response.reference_number);
+    if let Some(path) = &options.state_file
+        && tokio::fs::try_exists(path).await?
+    {
+        let saved = BatchUploadState::load(path).await?;
+        if saved.reference_number == session_response.reference_number {
+            state.completed_ordinals = saved.completed_ordinals;
+        }
+    }
+    state
+        .completed_ordinals
+        .extend(options.completed_ordinals.iter().copied());
+
+    let mut report = BatchUploadReport::default();
+    let mut pending = Vec::new();
     for request in &session_response.part_upload_requests {
+        if state.completed_ordinals.contains(&request.ordinal_number) {
+            report.skipped.insert(request.ordinal_number);
+            continue;
+        }
+
         let part = parts
             .iter()
-            .find(|p| p.ordinal_number == request.ordinal_number)
+            .find(|p| p.ordinal_number() == request.ordinal_number)
             .ok_or_else(|| {
                 KsefError::ApplicationError(
                     0,
@@ -23,28 +221,255 @@ pub async fn upload_batch_parts(
                     ),
                 )
             })?;
+        pending.push((request, part));
+    }
 
-        let method = Method::from_str(&request.method)
-            .map_err(|e| KsefError::ApplicationError(0, format!("Invalid HTTP method: {}", e)))?;
+    let state = Mutex::new(state);
+    let mut uploads = Vec::with_capacity(pending.len());
+    for (request, part) in pending {
+        uploads.push(upload_and_record(client, request, part, options, &state));
+    }
 
-        let mut req_builder = client
-            .client
-            .request(method, &request.url)
-            .body(part.content.clone());
+    let results = stream::iter(uploads)
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
 
-        for (key, value) in &request.headers {
-            req_builder = req_builder.header(key, value);
+    for (ordinal_number, result, save_error) in results {
+        match result {
+            Ok(()) => {
+                report.uploaded.insert(ordinal_number);
+            }
+            Err(e) => {
+                report.failed.insert(ordinal_number, e);
+            }
+        }
+        if report.state_save_error.is_none() {
+            report.state_save_error = save_error;
         }
+    }
 
-        let resp = req_builder.send().await?;
-        let status = resp.status();
+    Ok(report)
+}
 
-        if !status.is_success() {
-            let code = status.as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(KsefError::from_api_response(code, body));
+async fn upload_and_record<P: BatchPart>(
+    client: &KsefClient,
+    request: &PartUploadRequest,
+    part: &P,
+    options: &BatchUploadOptions,
+    state: &Mutex<BatchUploadState>,
+) -> (usize, Result<(), KsefError>, Option<KsefError>) {
+    let result = options
+        .retry_policy
+        .run(|| async { upload_part(client, request, part.body().await?).await })
+        .await;
+
+    let mut save_error = None;
+    if result.is_ok() {
+        let mut state = state.lock().await;
+        state.completed_ordinals.insert(request.ordinal_number);
+        if let Some(path) = &options.state_file {
+            save_error = state.save(path).await.err();
         }
     }
 
+    (request.ordinal_number, result, save_error)
+}
+
+async fn upload_part(
+    client: &KsefClient,
+    request: &PartUploadRequest,
+    body: PartBody,
+) -> Result<(), KsefError> {
+    let method = Method::from_str(&request.method)
+        .map_err(|e| KsefError::ApplicationError(0, format!("Invalid HTTP method: {}", e)))?;
+
+    let mut req_builder = client
+        .client
+        .request(method, &request.url)
+        .header(CONTENT_LENGTH, body.content_length)
+        .body(body.body);
+
+    for (key, value) in &request.headers {
+        req_builder = req_builder.header(key, value);
+    }
+
+    let resp = req_builder.send().await?;
+    let status = resp.status();
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
+    }
+
+    if !status.is_success() {
+        let code = status.as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(code, body));
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::batch_session::zip::FileMetadata;
+    use crate::client::models::{ContextIdentifier, ContextIdentifierType};
+    use bytes::Bytes;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn serve(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/part", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = bodies.clone();
+
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let header_end = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                let content_length: usize = headers
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .map(|v| v.trim().parse().unwrap())
+                    .unwrap_or(0);
+                while request.len() < header_end + content_length {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                received
+                    .lock()
+                    .await
+                    .push(request[header_end..header_end + content_length].to_vec());
+
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+
+        (url, bodies)
+    }
+
+    fn upload_setup(url: String) -> (KsefClient, OpenBatchSessionResponse, EncryptedBatchPart) {
+        let client = KsefClient::new_with_base(
+            "http://127.0.0.1",
+            ContextIdentifier {
+                id_type: ContextIdentifierType::Nip,
+                value: "1234567890".to_string(),
+            },
+        );
+        let response = OpenBatchSessionResponse {
+            reference_number: "20250101-SB-0000000000-0000000000-00".to_string(),
+            part_upload_requests: vec![PartUploadRequest {
+                ordinal_number: 1,
+                method: "PUT".to_string(),
+                url,
+                headers: HashMap::new(),
+            }],
+        };
+        let content = Bytes::from_static(b"encrypted part content");
+        let part = EncryptedBatchPart {
+            metadata: FileMetadata {
+                hash: Vec::new(),
+                size: content.len(),
+            },
+            content,
+            ordinal_number: 1,
+        };
+        (client, response, part)
+    }
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy::default()
+            .with_max_retries(2)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_upload_retries_in_memory_part() {
+        let (url, bodies) = serve(vec![503, 200]).await;
+        let (client, response, part) = upload_setup(url);
+        let options = BatchUploadOptions::new().with_retry_policy(fast_retries());
+
+        let report = upload_batch_parts_with_options(&client, &response, &[part], &options)
+            .await
+            .unwrap();
+
+        assert_eq!(report.uploaded, BTreeSet::from([1]));
+        assert!(report.is_complete());
+        let bodies = bodies.lock().await;
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies.iter().all(|b| b == b"encrypted part content"));
+    }
+
+    #[tokio::test]
+    async fn test_upload_state_save_error_keeps_report() {
+        let (url, _) = serve(vec![200]).await;
+        let (client, response, part) = upload_setup(url);
+        let dir = std::env::temp_dir().join(format!("ksef-upload-blocked-{}", std::process::id()));
+        let state_file = dir.join("state.json");
+        tokio::fs::create_dir_all(crate::client::fs::tmp_path(&state_file))
+            .await
+            .unwrap();
+        let options = BatchUploadOptions::new()
+            .with_retry_policy(fast_retries())
+            .with_state_file(&state_file);
+
+        let report = upload_batch_parts_with_options(&client, &response, &[part], &options)
+            .await
+            .unwrap();
+
+        assert_eq!(report.uploaded, BTreeSet::from([1]));
+        assert!(report.state_save_error.is_some());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_upload_state_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("ksef-upload-state-{}.json", std::process::id()));
+
+        let mut state = BatchUploadState::new("20250101-SB-0000000000-0000000000-00");
+        state.completed_ordinals.extend([1, 3]);
+        state.save(&path).await.unwrap();
+
+        let loaded = BatchUploadState::load(&path).await.unwrap();
+        assert_eq!(loaded.reference_number, state.reference_number);
+        assert_eq!(loaded.completed_ordinals, BTreeSet::from([1, 3]));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn test_upload_report_result() {
+        let mut report = BatchUploadReport::default();
+        report.uploaded.insert(2);
+        report.skipped.insert(1);
+        assert_eq!(report.completed_ordinals(), BTreeSet::from([1, 2]));
+        assert!(report.is_complete());
+
+        report
+            .failed
+            .insert(3, KsefError::ApiErrorRaw(500, "error".to_string()));
+        assert!(!report.is_complete());
+        assert!(report.into_result().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::error::KsefError;
 use crate::client::models::InvoicePayload;
 use crate::client::online_session::encryption::encrypt_invoice;
+use bytes::Bytes;
 use openssl::sha::sha256;
 use std::io::{Cursor, Write};
 use zip::ZipWriter;
@@ -18,7 +19,7 @@ pub struct BatchZipResult {
 }
 
 pub struct EncryptedBatchPart {
-    pub content: Vec<u8>,
+    pub content: Bytes,
     pub metadata: FileMetadata,
     pub ordinal_number: usize,
 }
@@ -36,7 +37,7 @@ pub fn encrypt_zip_parts(
         let size = encrypted_content.len();
 
         encrypted_parts.push(EncryptedBatchPart {
-            content: encrypted_content,
+            content: Bytes::from(encrypted_content),
             metadata: FileMetadata {
                 hash: hash.to_vec(),
                 size,
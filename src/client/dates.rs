@@ -0,0 +1,66 @@
+use chrono::{DateTime, Days, NaiveDate, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+
+pub const KSEF_TIME_ZONE: Tz = chrono_tz::Europe::Warsaw;
+
+pub fn to_warsaw(at: DateTime<Utc>) -> DateTime<Tz> {
+    at.with_timezone(&KSEF_TIME_ZONE)
+}
+
+pub fn warsaw_date(at: DateTime<Utc>) -> NaiveDate {
+    to_warsaw(at).date_naive()
+}
+
+pub fn today_in_warsaw() -> NaiveDate {
+    warsaw_date(Utc::now())
+}
+
+pub fn start_of_warsaw_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    KSEF_TIME_ZONE
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+pub fn end_of_warsaw_day(date: NaiveDate) -> DateTime<Utc> {
+    let next_day = date
+        .checked_add_days(Days::new(1))
+        .unwrap_or(NaiveDate::MAX);
+    start_of_warsaw_day(next_day) - TimeDelta::milliseconds(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_warsaw_business_dates() {
+        let winter = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        assert_eq!(start_of_warsaw_day(winter), utc("2025-01-14T23:00:00Z"));
+        assert_eq!(end_of_warsaw_day(winter), utc("2025-01-15T22:59:59.999Z"));
+
+        let summer = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        assert_eq!(start_of_warsaw_day(summer), utc("2025-06-30T22:00:00Z"));
+
+        let dst_change = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+        assert_eq!(
+            end_of_warsaw_day(dst_change),
+            utc("2025-03-30T21:59:59.999Z")
+        );
+
+        assert_eq!(
+            warsaw_date(utc("2025-06-30T22:30:00Z")),
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()
+        );
+        assert_eq!(
+            warsaw_date(utc("2025-01-15T22:30:00Z")),
+            NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()
+        );
+    }
+}
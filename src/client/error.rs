@@ -1,4 +1,8 @@
+use crate::client::preflight::PreflightReport;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
 use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -29,6 +33,44 @@ pub struct KsefExceptionDetail {
     pub exception_description: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityCheck {
+    Size,
+    Hash,
+    EncryptedSize,
+    EncryptedHash,
+}
+
+impl fmt::Display for IntegrityCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IntegrityCheck::Size => "size",
+            IntegrityCheck::Hash => "SHA-256 hash",
+            IntegrityCheck::EncryptedSize => "encrypted size",
+            IntegrityCheck::EncryptedHash => "encrypted SHA-256 hash",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityMismatch {
+    pub subject: String,
+    pub check: IntegrityCheck,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for IntegrityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} mismatch for {}: expected {}, got {}",
+            self.check, self.subject, self.expected, self.actual
+        )
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum KsefError {
     #[error("HTTP request error: {0}")]
@@ -52,12 +94,21 @@ pub enum KsefError {
     #[error("Request timed out")]
     TimeoutError,
 
+    #[error("Rate limit exceeded, retry after {0:?}")]
+    RateLimited(Option<Duration>),
+
     #[error("Runtime initialization error: {0}")]
     RuntimeError(String),
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
 
+    #[error("Pre-flight validation failed: {0}")]
+    PreflightError(Box<PreflightReport>),
+
+    #[error("Integrity check failed: {0}")]
+    IntegrityError(Box<IntegrityMismatch>),
+
     #[error("Application error: Code {0} - {1}")]
     ApplicationError(i32, String),
 
@@ -73,10 +124,33 @@ impl KsefError {
         }
     }
 
+    pub fn rate_limited(headers: &HeaderMap) -> Self {
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        KsefError::RateLimited(retry_after)
+    }
+
+    pub fn integrity(
+        subject: impl Into<String>,
+        check: IntegrityCheck,
+        expected: impl ToString,
+        actual: impl ToString,
+    ) -> Self {
+        KsefError::IntegrityError(Box::new(IntegrityMismatch {
+            subject: subject.into(),
+            check,
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        }))
+    }
+
     pub fn is_api_error(&self) -> bool {
         matches!(
             self,
-            KsefError::ApiError(_, _) | KsefError::ApiErrorRaw(_, _)
+            KsefError::ApiError(_, _) | KsefError::ApiErrorRaw(_, _) | KsefError::RateLimited(_)
         )
     }
 
@@ -84,10 +158,33 @@ impl KsefError {
         match self {
             KsefError::ApiError(code, _) => Some(*code),
             KsefError::ApiErrorRaw(code, _) => Some(*code),
+            KsefError::RateLimited(_) => Some(429),
             _ => None,
         }
     }
 
+    pub fn is_transient(&self) -> bool {
+        match self {
+            KsefError::RateLimited(_) | KsefError::TimeoutError => true,
+            KsefError::RequestError(e) => e.is_timeout() || e.is_connect(),
+            KsefError::ApiError(code, _) | KsefError::ApiErrorRaw(code, _) => {
+                *code == 429 || *code >= 500
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_safe_to_resend(&self) -> bool {
+        match self {
+            KsefError::RateLimited(_) => true,
+            KsefError::RequestError(e) => e.is_connect(),
+            KsefError::ApiError(code, _) | KsefError::ApiErrorRaw(code, _) => {
+                *code == 429 || *code == 503
+            }
+            _ => false,
+        }
+    }
+
     pub fn api_exception(&self) -> Option<&KsefApiException> {
         match self {
             KsefError::ApiError(_, ex) => Some(ex.as_ref()),
@@ -0,0 +1,248 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::fetching_invoices::export_invoices::{
+    ExportResult, ExportedInvoices, export_invoices,
+};
+use crate::client::fetching_invoices::fetch_invoice_metadata::{
+    DateRange, DateType, InvoiceMetadata, QueryCriteria,
+};
+use crate::client::fetching_invoices::stream_invoice_metadata::stream_invoice_metadata;
+use chrono::{DateTime, Months, TimeDelta, Utc};
+use futures::future;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashSet;
+
+pub const MAX_QUERY_WINDOW_MONTHS: u32 = 3;
+
+pub fn validate_date_range(date_range: &DateRange) -> Result<(), KsefError> {
+    if let Some(to) = date_range.to
+        && to < date_range.from
+    {
+        return Err(KsefError::ApplicationError(
+            0,
+            format!(
+                "Date range 'to' ({}) is earlier than 'from' ({})",
+                to.to_rfc3339(),
+                date_range.from.to_rfc3339()
+            ),
+        ));
+    }
+
+    if date_range.restrict_to_permanent_storage_hwm_date == Some(true)
+        && !matches!(date_range.date_type, DateType::PermanentStorage)
+    {
+        return Err(KsefError::ApplicationError(
+            0,
+            format!(
+                "restrictToPermanentStorageHwmDate can only be used with DateType::PermanentStorage, not {:?}",
+                date_range.date_type
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn split_query(query: &QueryCriteria) -> Result<Vec<QueryCriteria>, KsefError> {
+    split_query_at(query, Utc::now())
+}
+
+fn split_query_at(
+    query: &QueryCriteria,
+    now: DateTime<Utc>,
+) -> Result<Vec<QueryCriteria>, KsefError> {
+    validate_date_range(&query.date_range)?;
+
+    let to = query.date_range.to.unwrap_or(now);
+    let mut windows = Vec::new();
+    let mut window_from = query.date_range.from;
+    loop {
+        let next_from = add_window(window_from)?;
+        let mut window = query.clone();
+        window.date_range.from = window_from;
+        if next_from >= to {
+            windows.push(window);
+            return Ok(windows);
+        }
+        // Both ends of a KSeF date range are inclusive, so windows must not share an instant.
+        window.date_range.to = Some(next_from - TimeDelta::milliseconds(1));
+        windows.push(window);
+        window_from = next_from;
+    }
+}
+
+pub fn stream_invoice_metadata_in_windows(
+    client: &KsefClient,
+    query: QueryCriteria,
+) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>> {
+    let windows = match split_query(&query) {
+        Ok(windows) => windows,
+        Err(e) => return stream::once(future::ready(Err(e))).boxed(),
+    };
+
+    let mut seen = HashSet::new();
+    stream::iter(windows)
+        .flat_map(move |window| stream_invoice_metadata(client, window))
+        .filter(move |item| {
+            future::ready(match item {
+                Ok(invoice) => seen.insert(invoice.ksef_number.clone()),
+                Err(_) => true,
+            })
+        })
+        .boxed()
+}
+
+#[derive(Debug, Default)]
+pub struct WindowedExportResult {
+    pub exports: Vec<ExportResult>,
+}
+
+impl WindowedExportResult {
+    pub fn invoices(&self) -> Result<ExportedInvoices, KsefError> {
+        let mut merged = ExportedInvoices::default();
+        for export in &self.exports {
+            merged.merge(export.invoices()?);
+        }
+        Ok(merged)
+    }
+}
+
+pub async fn export_invoices_in_windows(
+    client: &KsefClient,
+    query: QueryCriteria,
+) -> Result<WindowedExportResult, KsefError> {
+    let mut result = WindowedExportResult::default();
+    for window in split_query(&query)? {
+        result.exports.push(export_invoices(client, window).await?);
+    }
+    Ok(result)
+}
+
+fn add_window(from: DateTime<Utc>) -> Result<DateTime<Utc>, KsefError> {
+    from.checked_add_months(Months::new(MAX_QUERY_WINDOW_MONTHS))
+        .ok_or_else(|| {
+            KsefError::ApplicationError(0, format!("Date range 'from' is out of range: {}", from))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fetching_invoices::fetch_invoice_metadata::{
+        DateRangeBuilder, QueryCriteriaBuilder, SubjectType,
+    };
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn query(date_type: DateType, from: &str, to: Option<&str>, hwm: bool) -> QueryCriteria {
+        let mut date_range = DateRangeBuilder::new().date_type(date_type).from(utc(from));
+        if let Some(to) = to {
+            date_range = date_range.to(utc(to));
+        }
+        if hwm {
+            date_range = date_range.restrict_to_permanent_storage_hwm_date(true);
+        }
+        QueryCriteriaBuilder::new()
+            .subject_type(SubjectType::Subject1)
+            .date_range(date_range.build().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn bounds(windows: &[QueryCriteria]) -> Vec<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+        windows
+            .iter()
+            .map(|w| (w.date_range.from, w.date_range.to))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_query() {
+        let now = utc("2025-06-15T12:00:00Z");
+
+        let short = query(
+            DateType::Invoicing,
+            "2025-01-01T00:00:00Z",
+            Some("2025-03-01T00:00:00Z"),
+            false,
+        );
+        assert_eq!(
+            bounds(&split_query_at(&short, now).unwrap()),
+            vec![(
+                utc("2025-01-01T00:00:00Z"),
+                Some(utc("2025-03-01T00:00:00Z"))
+            )]
+        );
+
+        let long = query(
+            DateType::Issue,
+            "2024-11-30T00:00:00Z",
+            Some("2025-06-01T00:00:00Z"),
+            false,
+        );
+        let windows = split_query_at(&long, now).unwrap();
+        assert!(windows.windows(2).all(|pair| {
+            pair[0]
+                .date_range
+                .to
+                .is_some_and(|to| to < pair[1].date_range.from)
+        }));
+        assert_eq!(
+            bounds(&windows),
+            vec![
+                (
+                    utc("2024-11-30T00:00:00Z"),
+                    Some(utc("2025-02-27T23:59:59.999Z"))
+                ),
+                (
+                    utc("2025-02-28T00:00:00Z"),
+                    Some(utc("2025-05-27T23:59:59.999Z"))
+                ),
+                (
+                    utc("2025-05-28T00:00:00Z"),
+                    Some(utc("2025-06-01T00:00:00Z"))
+                ),
+            ]
+        );
+
+        let open = query(
+            DateType::PermanentStorage,
+            "2025-01-01T00:00:00Z",
+            None,
+            true,
+        );
+        let windows = split_query_at(&open, now).unwrap();
+        assert_eq!(
+            bounds(&windows),
+            vec![
+                (
+                    utc("2025-01-01T00:00:00Z"),
+                    Some(utc("2025-03-31T23:59:59.999Z"))
+                ),
+                (utc("2025-04-01T00:00:00Z"), None),
+            ]
+        );
+        assert!(
+            windows
+                .iter()
+                .all(|w| w.date_range.restrict_to_permanent_storage_hwm_date == Some(true))
+        );
+    }
+
+    #[test]
+    fn test_validate_date_range() {
+        let hwm_on_issue = query(DateType::Issue, "2025-01-01T00:00:00Z", None, true);
+        assert!(validate_date_range(&hwm_on_issue.date_range).is_err());
+        assert!(split_query(&hwm_on_issue).is_err());
+
+        let reversed = query(
+            DateType::Invoicing,
+            "2025-02-01T00:00:00Z",
+            Some("2025-01-01T00:00:00Z"),
+            false,
+        );
+        assert!(validate_date_range(&reversed.date_range).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::client::KsefClient;
+use crate::client::error::{IntegrityCheck, KsefError};
+use crate::client::fetching_invoices::export_invoices::{
+    ExportInvoicesStatusResponse, ExportPackage, ExportPackagePart, request_export, wait_for_export,
+};
+use crate::client::fetching_invoices::fetch_invoice_metadata::QueryCriteria;
+use crate::client::fs::{rename_durably, tmp_path};
+use crate::client::online_session::encryption::EncryptionData;
+use crate::client::retry::RetryPolicy;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use futures::stream::{self, StreamExt};
+use openssl::sha::Sha256;
+use openssl::symm::{Cipher, Crypter, Mode};
+use reqwest::Method;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Debug, Clone)]
+pub struct ExportDownloadOptions {
+    pub concurrency: usize,
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for ExportDownloadOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl ExportDownloadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadedPart {
+    pub metadata: ExportPackagePart,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportDownload {
+    pub status: ExportInvoicesStatusResponse,
+    pub parts: Vec<DownloadedPart>,
+}
+
+pub async fn download_export_to(
+    client: &KsefClient,
+    query: QueryCriteria,
+    dir: &Path,
+) -> Result<ExportDownload, KsefError> {
+    download_export_to_with_options(client, query, dir, &ExportDownloadOptions::default()).await
+}
+
+pub async fn download_export_to_with_options(
+    client: &KsefClient,
+    query: QueryCriteria,
+    dir: &Path,
+    options: &ExportDownloadOptions,
+) -> Result<ExportDownload, KsefError> {
+    let (reference_number, encryption_data) = request_export(client, query).await?;
+    let (status, package) = wait_for_export(client, &reference_number).await?;
+    let parts = download_export_package(client, &package, &encryption_data, dir, options).await?;
+    Ok(ExportDownload { status, parts })
+}
+
+pub async fn download_export_package(
+    client: &KsefClient,
+    package: &ExportPackage,
+    encryption_data: &EncryptionData,
+    dir: &Path,
+    options: &ExportDownloadOptions,
+) -> Result<Vec<DownloadedPart>, KsefError> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    let mut downloads = Vec::with_capacity(package.parts.len());
+    for part in &package.parts {
+        downloads.push(download_with_retry(
+            client,
+            part,
+            encryption_data,
+            dir,
+            options,
+        ));
+    }
+
+    let results = stream::iter(downloads)
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut parts = results.into_iter().collect::<Result<Vec<_>, _>>()?;
+    parts.sort_by_key(|p| p.metadata.ordinal_number);
+    Ok(parts)
+}
+
+async fn download_with_retry(
+    client: &KsefClient,
+    part: &ExportPackagePart,
+    encryption_data: &EncryptionData,
+    dir: &Path,
+    options: &ExportDownloadOptions,
+) -> Result<DownloadedPart, KsefError> {
+    options
+        .retry_policy
+        .run(|| download_part(client, part, encryption_data, dir))
+        .await
+}
+
+async fn download_part(
+    client: &KsefClient,
+    part: &ExportPackagePart,
+    encryption_data: &EncryptionData,
+    dir: &Path,
+) -> Result<DownloadedPart, KsefError> {
+    let path = dir.join(part_file_name(part));
+    let tmp_path = tmp_path(&path);
+
+    let result = write_part(client, part, encryption_data, &tmp_path).await;
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+
+    rename_durably(&tmp_path, &path).await?;
+    Ok(DownloadedPart {
+        metadata: part.clone(),
+        path,
+    })
+}
+
+async fn write_part(
+    client: &KsefClient,
+    part: &ExportPackagePart,
+    encryption_data: &EncryptionData,
+    tmp_path: &Path,
+) -> Result<(), KsefError> {
+    let method = Method::from_str(&part.method)
+        .map_err(|e| KsefError::ApplicationError(0, format!("Invalid HTTP method: {}", e)))?;
+
+    let mut resp = client.client.request(method, &part.url).send().await?;
+    let status = resp.status();
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
+    }
+
+    if !status.is_success() {
+        let code = status.as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(code, body));
+    }
+
+    let mut decryptor = PartDecryptor::new(encryption_data, part)?;
+    let mut file = tokio::fs::File::create(tmp_path).await?;
+    while let Some(chunk) = resp.chunk().await? {
+        let decrypted = decryptor.update(&chunk)?;
+        file.write_all(&decrypted).await?;
+    }
+    let tail = decryptor.finalize()?;
+    file.write_all(&tail).await?;
+    file.sync_all().await?;
+    Ok(())
+}
+
+fn part_file_name(part: &ExportPackagePart) -> String {
+    let name = Path::new(&part.part_name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.strip_suffix(".aes").unwrap_or(n))
+        .unwrap_or_default();
+
+    if name.is_empty() {
+        format!("part-{}.zip", part.ordinal_number)
+    } else {
+        name.to_string()
+    }
+}
+
+struct PartDecryptor<'a> {
+    part: &'a ExportPackagePart,
+    crypter: Crypter,
+    block_size: usize,
+    encrypted_hasher: Sha256,
+    encrypted_size: i64,
+    hasher: Sha256,
+    size: i64,
+}
+
+impl<'a> PartDecryptor<'a> {
+    fn new(
+        encryption_data: &EncryptionData,
+        part: &'a ExportPackagePart,
+    ) -> Result<Self, KsefError> {
+        let cipher = Cipher::aes_256_cbc();
+        let crypter = Crypter::new(
+            cipher,
+            Mode::Decrypt,
+            &encryption_data.symmetric_key,
+            Some(&encryption_data.initialization_vector),
+        )?;
+
+        Ok(Self {
+            part,
+            crypter,
+            block_size: cipher.block_size(),
+            encrypted_hasher: Sha256::new(),
+            encrypted_size: 0,
+            hasher: Sha256::new(),
+            size: 0,
+        })
+    }
+
+    fn update(&mut self, chunk: &[u8]) -> Result<Vec<u8>, KsefError> {
+        self.encrypted_hasher.update(chunk);
+        self.encrypted_size += chunk.len() as i64;
+
+        let mut buffer = vec![0; chunk.len() + self.block_size];
+        let count = self.crypter.update(chunk, &mut buffer)?;
+        buffer.truncate(count);
+
+        self.hasher.update(&buffer);
+        self.size += count as i64;
+        Ok(buffer)
+    }
+
+    fn finalize(mut self) -> Result<Vec<u8>, KsefError> {
+        let subject = format!("export part {}", self.part.part_name);
+
+        if self.encrypted_size != self.part.encrypted_part_size {
+            return Err(KsefError::integrity(
+                subject,
+                IntegrityCheck::EncryptedSize,
+                self.part.encrypted_part_size,
+                self.encrypted_size,
+            ));
+        }
+        let encrypted_hash = BASE64.encode(self.encrypted_hasher.finish());
+        if encrypted_hash != self.part.encrypted_part_hash {
+            return Err(KsefError::integrity(
+                subject,
+                IntegrityCheck::EncryptedHash,
+                &self.part.encrypted_part_hash,
+                encrypted_hash,
+            ));
+        }
+
+        let mut buffer = vec![0; self.block_size];
+        let count = self.crypter.finalize(&mut buffer)?;
+        buffer.truncate(count);
+        self.hasher.update(&buffer);
+        self.size += count as i64;
+
+        if self.size != self.part.part_size {
+            return Err(KsefError::integrity(
+                subject,
+                IntegrityCheck::Size,
+                self.part.part_size,
+                self.size,
+            ));
+        }
+        let hash = BASE64.encode(self.hasher.finish());
+        if hash != self.part.part_hash {
+            return Err(KsefError::integrity(
+                subject,
+                IntegrityCheck::Hash,
+                &self.part.part_hash,
+                hash,
+            ));
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::sha::sha256;
+    use openssl::symm::encrypt;
+
+    fn encryption_data() -> EncryptionData {
+        EncryptionData {
+            encrypted_symmetric_key: Vec::new(),
+            initialization_vector: vec![7; 16],
+            symmetric_key: vec![3; 32],
+        }
+    }
+
+    fn part_for(plain: &[u8], encrypted: &[u8]) -> ExportPackagePart {
+        ExportPackagePart {
+            ordinal_number: 1,
+            part_name: "export-1.zip.aes".to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com/export-1.zip.aes".to_string(),
+            part_size: plain.len() as i64,
+            part_hash: BASE64.encode(sha256(plain)),
+            encrypted_part_size: encrypted.len() as i64,
+            encrypted_part_hash: BASE64.encode(sha256(encrypted)),
+            expiration_date: "2025-01-01T00:00:00Z".parse().unwrap(),
+        }
+    }
+
+    fn decrypt_chunked(part: &ExportPackagePart, encrypted: &[u8]) -> Result<Vec<u8>, KsefError> {
+        let data = encryption_data();
+        let mut decryptor = PartDecryptor::new(&data, part)?;
+        let mut output = Vec::new();
+        for chunk in encrypted.chunks(7) {
+            output.extend(decryptor.update(chunk)?);
+        }
+        output.extend(decryptor.finalize()?);
+        Ok(output)
+    }
+
+    #[test]
+    fn test_part_decryptor_verifies_hashes() {
+        let data = encryption_data();
+        let plain: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let encrypted = encrypt(
+            Cipher::aes_256_cbc(),
+            &data.symmetric_key,
+            Some(&data.initialization_vector),
+            &plain,
+        )
+        .unwrap();
+
+        let part = part_for(&plain, &encrypted);
+        assert_eq!(decrypt_chunked(&part, &encrypted).unwrap(), plain);
+        assert_eq!(part_file_name(&part), "export-1.zip");
+
+        let mut tampered = encrypted.clone();
+        tampered[10] ^= 1;
+        match decrypt_chunked(&part, &tampered) {
+            Err(KsefError::IntegrityError(mismatch)) => {
+                assert_eq!(mismatch.check, IntegrityCheck::EncryptedHash)
+            }
+            other => panic!("expected integrity error, got {:?}", other),
+        }
+
+        let mut wrong_plain_hash = part.clone();
+        wrong_plain_hash.part_hash = BASE64.encode(sha256(b"other"));
+        match decrypt_chunked(&wrong_plain_hash, &encrypted) {
+            Err(KsefError::IntegrityError(mismatch)) => {
+                assert_eq!(mismatch.check, IntegrityCheck::Hash)
+            }
+            other => panic!("expected integrity error, got {:?}", other),
+        }
+    }
+}
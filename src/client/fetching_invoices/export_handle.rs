@@ -0,0 +1,274 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::fetching_invoices::download_export::{
+    ExportDownload, ExportDownloadOptions, download_export_package,
+};
+use crate::client::fetching_invoices::export_invoices::{
+    ExportInvoicesStatusResponse, ExportPackage, ExportResult, download_parts, request_export,
+    wait_for_export,
+};
+use crate::client::fetching_invoices::fetch_invoice_metadata::QueryCriteria;
+use crate::client::fs::write_atomically;
+use crate::client::online_session::encryption::EncryptionData;
+use crate::client::types::ReferenceNumber;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use openssl::rand::rand_bytes;
+use openssl::symm::{Cipher, decrypt_aead, encrypt_aead};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const EXPORT_HANDLE_VERSION: u32 = 1;
+
+const WRAPPING_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHandle {
+    pub version: u32,
+    pub reference_number: ReferenceNumber,
+    pub wrapped_symmetric_key: String,
+    pub initialization_vector: String,
+    pub created_at: DateTime<Utc>,
+    pub package_expiration_date: Option<DateTime<Utc>>,
+}
+
+impl ExportHandle {
+    pub fn new(
+        reference_number: ReferenceNumber,
+        encryption_data: &EncryptionData,
+        wrapping_key: &[u8],
+    ) -> Result<Self, KsefError> {
+        check_wrapping_key(wrapping_key)?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand_bytes(&mut nonce)?;
+        let mut tag = [0u8; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            wrapping_key,
+            Some(&nonce),
+            reference_number.as_bytes(),
+            &encryption_data.symmetric_key,
+            &mut tag,
+        )?;
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend(ciphertext);
+        wrapped.extend(tag);
+
+        Ok(Self {
+            version: EXPORT_HANDLE_VERSION,
+            reference_number,
+            wrapped_symmetric_key: BASE64.encode(wrapped),
+            initialization_vector: BASE64.encode(&encryption_data.initialization_vector),
+            created_at: Utc::now(),
+            package_expiration_date: None,
+        })
+    }
+
+    pub fn generate_wrapping_key() -> Result<[u8; WRAPPING_KEY_LEN], KsefError> {
+        let mut key = [0u8; WRAPPING_KEY_LEN];
+        rand_bytes(&mut key)?;
+        Ok(key)
+    }
+
+    pub fn encryption_data(&self, wrapping_key: &[u8]) -> Result<EncryptionData, KsefError> {
+        check_wrapping_key(wrapping_key)?;
+
+        let wrapped = BASE64.decode(&self.wrapped_symmetric_key).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Invalid wrapped symmetric key: {}", e))
+        })?;
+        if wrapped.len() < NONCE_LEN + TAG_LEN {
+            return Err(KsefError::ApplicationError(
+                0,
+                "Wrapped symmetric key is too short".to_string(),
+            ));
+        }
+        let (nonce, rest) = wrapped.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+
+        let symmetric_key = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            wrapping_key,
+            Some(nonce),
+            self.reference_number.as_bytes(),
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| {
+            KsefError::ApplicationError(
+                0,
+                format!(
+                    "Failed to unwrap the key of export {}; check the wrapping key",
+                    self.reference_number
+                ),
+            )
+        })?;
+
+        let initialization_vector = BASE64.decode(&self.initialization_vector).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Invalid initialization vector: {}", e))
+        })?;
+
+        Ok(EncryptionData {
+            encrypted_symmetric_key: Vec::new(),
+            initialization_vector,
+            symmetric_key,
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.package_expiration_date
+            .is_some_and(|expiration| expiration <= Utc::now())
+    }
+
+    pub fn from_json(json: &[u8]) -> Result<Self, KsefError> {
+        let handle: Self = serde_json::from_slice(json)?;
+        if handle.version > EXPORT_HANDLE_VERSION {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Unsupported export handle version {} (supported up to {})",
+                    handle.version, EXPORT_HANDLE_VERSION
+                ),
+            ));
+        }
+        Ok(handle)
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, KsefError> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, KsefError> {
+        let content = tokio::fs::read(path).await?;
+        Self::from_json(&content)
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), KsefError> {
+        write_atomically(path, &self.to_json()?).await
+    }
+
+    fn check_not_expired(&self) -> Result<(), KsefError> {
+        match self.package_expiration_date {
+            Some(expiration) if self.is_expired() => Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Export package {} expired at {}",
+                    self.reference_number, expiration
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn check_wrapping_key(wrapping_key: &[u8]) -> Result<(), KsefError> {
+    if wrapping_key.len() != WRAPPING_KEY_LEN {
+        return Err(KsefError::ApplicationError(
+            0,
+            format!(
+                "Wrapping key must be {} bytes long, got {}",
+                WRAPPING_KEY_LEN,
+                wrapping_key.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn start_resumable_export(
+    client: &KsefClient,
+    query: QueryCriteria,
+    wrapping_key: &[u8],
+) -> Result<ExportHandle, KsefError> {
+    check_wrapping_key(wrapping_key)?;
+    let (reference_number, encryption_data) = request_export(client, query).await?;
+    ExportHandle::new(reference_number, &encryption_data, wrapping_key)
+}
+
+pub async fn resume_export(
+    client: &KsefClient,
+    handle_path: &Path,
+    wrapping_key: &[u8],
+) -> Result<ExportResult, KsefError> {
+    let handle = ExportHandle::load(handle_path).await?;
+    let encryption_data = handle.encryption_data(wrapping_key)?;
+    let (status, package) = wait_for_handle(client, handle, handle_path).await?;
+    let parts = download_parts(client, package, &encryption_data).await?;
+    Ok(ExportResult { status, parts })
+}
+
+pub async fn resume_export_to(
+    client: &KsefClient,
+    handle_path: &Path,
+    wrapping_key: &[u8],
+    dir: &Path,
+    options: &ExportDownloadOptions,
+) -> Result<ExportDownload, KsefError> {
+    let handle = ExportHandle::load(handle_path).await?;
+    let encryption_data = handle.encryption_data(wrapping_key)?;
+    let (status, package) = wait_for_handle(client, handle, handle_path).await?;
+    let parts = download_export_package(client, &package, &encryption_data, dir, options).await?;
+    Ok(ExportDownload { status, parts })
+}
+
+async fn wait_for_handle(
+    client: &KsefClient,
+    mut handle: ExportHandle,
+    handle_path: &Path,
+) -> Result<(ExportInvoicesStatusResponse, ExportPackage), KsefError> {
+    handle.check_not_expired()?;
+
+    let (status, package) = wait_for_export(client, &handle.reference_number).await?;
+    if let Some(expiration) = status.status.package_expiration_date
+        && handle.package_expiration_date != Some(expiration)
+    {
+        handle.package_expiration_date = Some(expiration);
+        handle.save(handle_path).await?;
+    }
+    handle.check_not_expired()?;
+
+    Ok((status, package))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_handle_roundtrip() {
+        let encryption_data = EncryptionData {
+            encrypted_symmetric_key: vec![1; 256],
+            initialization_vector: vec![2; 16],
+            symmetric_key: vec![3; 32],
+        };
+        let wrapping_key = ExportHandle::generate_wrapping_key().unwrap();
+        let reference_number = ReferenceNumber::new("20250625-EH-2C2E4BB000-8F6E3A1B2C-D4");
+
+        let mut handle =
+            ExportHandle::new(reference_number, &encryption_data, &wrapping_key).unwrap();
+        assert!(!handle.is_expired());
+
+        let json = handle.to_json().unwrap();
+        assert!(!String::from_utf8_lossy(&json).contains(&BASE64.encode([3u8; 32])));
+
+        let loaded = ExportHandle::from_json(&json).unwrap();
+        assert_eq!(loaded, handle);
+        let unwrapped = loaded.encryption_data(&wrapping_key).unwrap();
+        assert_eq!(unwrapped.symmetric_key, encryption_data.symmetric_key);
+        assert_eq!(
+            unwrapped.initialization_vector,
+            encryption_data.initialization_vector
+        );
+
+        assert!(loaded.encryption_data(&[0u8; 32]).is_err());
+        assert!(loaded.encryption_data(&[0u8; 16]).is_err());
+
+        handle.package_expiration_date = Some(Utc::now() - chrono::Duration::minutes(1));
+        assert!(handle.is_expired());
+        assert!(handle.check_not_expired().is_err());
+    }
+}
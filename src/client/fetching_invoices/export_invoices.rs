@@ -1,11 +1,17 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::fetching_invoices::fetch_invoice_metadata::{InvoiceMetadata, QueryCriteria};
+use crate::client::online_session::encryption::EncryptionData;
 use crate::client::routes;
+use crate::client::status_codes::ExportStatusCode;
 use crate::client::traits::*;
 use crate::client::types::ReferenceNumber;
+use chrono::{DateTime, NaiveDate, Utc};
 use openssl::symm::{Cipher, decrypt};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
 use std::time::Duration;
 use tokio::time::sleep;
 
@@ -42,15 +48,15 @@ pub struct ExportInvoicesStatusResponse {
 #[derive(Debug, Deserialize, Clone)]
 pub struct ExportStatus {
     #[serde(rename = "code")]
-    pub code: i32,
+    pub code: ExportStatusCode,
     #[serde(rename = "description")]
     pub description: String,
     #[serde(rename = "details")]
     pub details: Option<Vec<String>>,
     #[serde(rename = "completedDate")]
-    pub completed_date: Option<String>,
+    pub completed_date: Option<DateTime<Utc>>,
     #[serde(rename = "packageExpirationDate")]
-    pub package_expiration_date: Option<String>,
+    pub package_expiration_date: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -64,13 +70,13 @@ pub struct ExportPackage {
     #[serde(rename = "isTruncated")]
     pub is_truncated: bool,
     #[serde(rename = "lastIssueDate")]
-    pub last_issue_date: Option<String>,
+    pub last_issue_date: Option<NaiveDate>,
     #[serde(rename = "lastInvoicingDate")]
-    pub last_invoicing_date: Option<String>,
+    pub last_invoicing_date: Option<DateTime<Utc>>,
     #[serde(rename = "lastPermanentStorageDate")]
-    pub last_permanent_storage_date: Option<String>,
+    pub last_permanent_storage_date: Option<DateTime<Utc>>,
     #[serde(rename = "permanentStorageHwmDate")]
-    pub permanent_storage_hwm_date: Option<String>,
+    pub permanent_storage_hwm_date: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -92,7 +98,7 @@ pub struct ExportPackagePart {
     #[serde(rename = "encryptedPartHash")]
     pub encrypted_part_hash: String,
     #[serde(rename = "expirationDate")]
-    pub expiration_date: String,
+    pub expiration_date: DateTime<Utc>,
 }
 
 #[derive(Debug)]
@@ -113,6 +119,148 @@ pub struct InvoicePackageMetadata {
     pub invoices: Vec<InvoiceMetadata>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportWarning {
+    MissingContent { ksef_number: String },
+    MissingMetadata { file_name: String },
+}
+
+impl fmt::Display for ExportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportWarning::MissingContent { ksef_number } => write!(
+                f,
+                "Content for KSeF number {} not found in package parts",
+                ksef_number
+            ),
+            ExportWarning::MissingMetadata { file_name } => {
+                write!(f, "No metadata found for invoice file {}", file_name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExportedInvoices {
+    invoices: Vec<(InvoiceMetadata, Vec<u8>)>,
+    warnings: Vec<ExportWarning>,
+}
+
+impl ExportedInvoices {
+    pub fn len(&self) -> usize {
+        self.invoices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invoices.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&InvoiceMetadata, &[u8])> {
+        self.invoices
+            .iter()
+            .map(|(metadata, content)| (metadata, content.as_slice()))
+    }
+
+    pub fn warnings(&self) -> &[ExportWarning] {
+        &self.warnings
+    }
+
+    pub(crate) fn merge(&mut self, other: ExportedInvoices) {
+        let mut seen: HashSet<String> = self
+            .invoices
+            .iter()
+            .map(|(metadata, _)| metadata.ksef_number.clone())
+            .collect();
+        self.invoices.extend(
+            other
+                .invoices
+                .into_iter()
+                .filter(|(metadata, _)| seen.insert(metadata.ksef_number.clone())),
+        );
+        self.warnings.extend(other.warnings);
+    }
+}
+
+impl IntoIterator for ExportedInvoices {
+    type Item = (InvoiceMetadata, Vec<u8>);
+    type IntoIter = std::vec::IntoIter<(InvoiceMetadata, Vec<u8>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.invoices.into_iter()
+    }
+}
+
+impl ExportResult {
+    pub fn invoices(&self) -> Result<ExportedInvoices, KsefError> {
+        let mut contents: HashMap<String, (String, Vec<u8>)> = HashMap::new();
+        let mut metadata = Vec::new();
+
+        for part in &self.parts {
+            let cursor = std::io::Cursor::new(&part.content);
+            let mut archive = zip::ZipArchive::new(cursor).map_err(|e| {
+                KsefError::ApplicationError(0, format!("Failed to open zip archive: {}", e))
+            })?;
+
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i).map_err(|e| {
+                    KsefError::ApplicationError(0, format!("Failed to read file in zip: {}", e))
+                })?;
+                let name = file.name().to_string();
+                let base_name = name.rsplit('/').next().unwrap_or(&name);
+
+                if let Some(stem) = base_name.strip_suffix(".xml") {
+                    let mut content = Vec::new();
+                    file.read_to_end(&mut content).map_err(|e| {
+                        KsefError::ApplicationError(0, format!("Failed to read xml content: {}", e))
+                    })?;
+                    contents.insert(stem.to_string(), (name, content));
+                } else if base_name.ends_with(".json") && base_name.contains("metadata") {
+                    let mut json_content = Vec::new();
+                    file.read_to_end(&mut json_content).map_err(|e| {
+                        KsefError::ApplicationError(
+                            0,
+                            format!("Failed to read metadata json: {}", e),
+                        )
+                    })?;
+
+                    let package: InvoicePackageMetadata = serde_json::from_slice(&json_content)
+                        .map_err(|e| {
+                            KsefError::ApplicationError(
+                                0,
+                                format!("Failed to parse metadata json: {}", e),
+                            )
+                        })?;
+                    metadata.extend(package.invoices);
+                }
+            }
+        }
+
+        let mut result = ExportedInvoices::default();
+        let mut seen = HashSet::new();
+        for meta in metadata {
+            if !seen.insert(meta.ksef_number.clone()) {
+                continue;
+            }
+            match contents.remove(&meta.ksef_number) {
+                Some((_, content)) => result.invoices.push((meta, content)),
+                None => result.warnings.push(ExportWarning::MissingContent {
+                    ksef_number: meta.ksef_number,
+                }),
+            }
+        }
+
+        let mut orphans: Vec<String> = contents.into_values().map(|(name, _)| name).collect();
+        orphans.sort();
+        result.warnings.extend(
+            orphans
+                .into_iter()
+                .map(|file_name| ExportWarning::MissingMetadata { file_name }),
+        );
+
+        Ok(result)
+    }
+}
+
 pub async fn start_export_invoices(
     client: &KsefClient,
     request: ExportInvoicesRequest,
@@ -188,10 +336,10 @@ pub async fn get_export_status(
     Ok(parsed)
 }
 
-pub async fn export_invoices(
+pub(crate) async fn request_export(
     client: &KsefClient,
     query: QueryCriteria,
-) -> Result<ExportResult, KsefError> {
+) -> Result<(ReferenceNumber, EncryptionData), KsefError> {
     use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
     let encryption_data = client.generate_encryption_data().await?;
@@ -205,8 +353,13 @@ pub async fn export_invoices(
     };
 
     let export_response = start_export_invoices(client, request).await?;
-    let reference_number = export_response.reference_number;
+    Ok((export_response.reference_number, encryption_data))
+}
 
+pub async fn wait_for_export(
+    client: &KsefClient,
+    reference_number: &ReferenceNumber,
+) -> Result<(ExportInvoicesStatusResponse, ExportPackage), KsefError> {
     let interval = Duration::from_secs(5);
     let timeout_duration = Duration::from_secs(300);
     let start_time = std::time::Instant::now();
@@ -218,59 +371,25 @@ pub async fn export_invoices(
             ));
         }
 
-        let status_response = get_export_status(client, &reference_number).await?;
+        let status_response = get_export_status(client, reference_number).await?;
 
         match status_response.status.code {
-            200 => {
-                let status_clone = status_response.clone();
-                if let Some(package) = status_response.package {
-                    let mut decrypted_parts = Vec::new();
-                    for part in package.parts {
-                        let part_bytes = client
-                            .client
-                            .get(&part.url)
-                            .send()
-                            .await?
-                            .bytes()
-                            .await?
-                            .to_vec();
-
-                        let decrypted_part = decrypt(
-                            Cipher::aes_256_cbc(),
-                            &encryption_data.symmetric_key,
-                            Some(&encryption_data.initialization_vector),
-                            &part_bytes,
-                        )
-                        .map_err(|e| {
-                            KsefError::ApplicationError(
-                                0,
-                                format!("Failed to decrypt export part: {}", e),
-                            )
-                        })?;
-
-                        decrypted_parts.push(ExportedPart {
-                            metadata: part,
-                            content: decrypted_part,
-                        });
-                    }
-                    return Ok(ExportResult {
-                        status: status_clone,
-                        parts: decrypted_parts,
-                    });
-                } else {
-                    return Err(KsefError::ApplicationError(
+            ExportStatusCode::Completed => {
+                return match status_response.package.clone() {
+                    Some(package) => Ok((status_response, package)),
+                    None => Err(KsefError::ApplicationError(
                         0,
                         "Export status 200 but no package details found".to_string(),
-                    ));
-                }
+                    )),
+                };
             }
-            100 => {
+            code if code.is_in_progress() => {
                 sleep(interval).await;
                 continue;
             }
             code => {
                 return Err(KsefError::ApiErrorRaw(
-                    code as u16,
+                    code.code() as u16,
                     format!(
                         "Export failed with status {}: {}",
                         code, status_response.status.description
@@ -280,3 +399,146 @@ pub async fn export_invoices(
         }
     }
 }
+
+pub async fn export_invoices(
+    client: &KsefClient,
+    query: QueryCriteria,
+) -> Result<ExportResult, KsefError> {
+    let (reference_number, encryption_data) = request_export(client, query).await?;
+    let (status, package) = wait_for_export(client, &reference_number).await?;
+
+    let parts = download_parts(client, package, &encryption_data).await?;
+
+    Ok(ExportResult { status, parts })
+}
+
+pub(crate) async fn download_parts(
+    client: &KsefClient,
+    package: ExportPackage,
+    encryption_data: &EncryptionData,
+) -> Result<Vec<ExportedPart>, KsefError> {
+    let mut decrypted_parts = Vec::new();
+    for part in package.parts {
+        let part_bytes = client
+            .client
+            .get(&part.url)
+            .send()
+            .await?
+            .bytes()
+            .await?
+            .to_vec();
+
+        let decrypted_part = decrypt(
+            Cipher::aes_256_cbc(),
+            &encryption_data.symmetric_key,
+            Some(&encryption_data.initialization_vector),
+            &part_bytes,
+        )
+        .map_err(|e| {
+            KsefError::ApplicationError(0, format!("Failed to decrypt export part: {}", e))
+        })?;
+
+        decrypted_parts.push(ExportedPart {
+            metadata: part,
+            content: decrypted_part,
+        });
+    }
+
+    Ok(decrypted_parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn metadata_json(ksef_numbers: &[&str]) -> String {
+        let invoices: Vec<serde_json::Value> = ksef_numbers
+            .iter()
+            .map(|ksef_number| {
+                serde_json::json!({
+                    "ksefNumber": ksef_number,
+                    "invoiceNumber": "FA/1/2025",
+                    "issueDate": "2025-06-25",
+                    "invoicingDate": "2025-06-26T10:00:00+00:00",
+                    "permanentStorageDate": "2025-06-26T10:00:05+00:00",
+                    "seller": { "nip": "5265877635" },
+                    "buyer": { "identifier": { "type": "None" } },
+                    "currency": "PLN",
+                    "invoicingMode": "Online",
+                    "invoiceType": "Vat",
+                    "formCode": { "systemCode": "FA (2)", "schemaVersion": "1-0E", "value": "FA" },
+                    "isSelfInvoicing": false,
+                    "hasAttachment": false,
+                    "invoiceHash": "hash",
+                })
+            })
+            .collect();
+        serde_json::json!({ "invoices": invoices }).to_string()
+    }
+
+    fn part(files: &[(&str, &str)]) -> ExportedPart {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        let content = zip.finish().unwrap().into_inner();
+
+        ExportedPart {
+            metadata: serde_json::from_value(serde_json::json!({
+                "ordinalNumber": 1,
+                "partName": "part-1.zip.aes",
+                "method": "GET",
+                "url": "https://example.com/part-1.zip.aes",
+                "partSize": content.len(),
+                "partHash": "",
+                "encryptedPartSize": 0,
+                "encryptedPartHash": "",
+                "expirationDate": "2025-07-01T00:00:00Z",
+            }))
+            .unwrap(),
+            content,
+        }
+    }
+
+    #[test]
+    fn test_export_result_invoices() {
+        let metadata = metadata_json(&["KSEF-1", "KSEF-2", "KSEF-1-1"]);
+        let result = ExportResult {
+            status: serde_json::from_value(serde_json::json!({
+                "status": { "code": 200, "description": "Eksport zakończony" },
+            }))
+            .unwrap(),
+            parts: vec![
+                part(&[("KSEF-1-1.xml", "<b/>"), ("_metadata.json", &metadata)]),
+                part(&[("KSEF-1.xml", "<a/>"), ("KSEF-3.xml", "<c/>")]),
+            ],
+        };
+
+        let invoices = result.invoices().unwrap();
+        let found: Vec<(&str, &[u8])> = invoices
+            .iter()
+            .map(|(meta, content)| (meta.ksef_number.as_str(), content))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("KSEF-1", b"<a/>".as_slice()),
+                ("KSEF-1-1", b"<b/>".as_slice())
+            ]
+        );
+        assert_eq!(
+            invoices.warnings(),
+            &[
+                ExportWarning::MissingContent {
+                    ksef_number: "KSEF-2".to_string()
+                },
+                ExportWarning::MissingMetadata {
+                    file_name: "KSEF-3.xml".to_string()
+                },
+            ]
+        );
+    }
+}
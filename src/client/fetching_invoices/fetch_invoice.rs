@@ -1,18 +1,83 @@
 use crate::client::KsefClient;
-use crate::client::error::KsefError;
+use crate::client::archive::store::{ArchiveEntry, ArchiveRecord, InvoiceArchive};
+use crate::client::error::{IntegrityCheck, KsefError};
+use crate::client::fetching_invoices::fetch_invoice_metadata::InvoiceMetadata;
 use crate::client::routes;
-use crate::client::types::KsefNumber;
+use crate::client::types::{InvoiceHash, KsefNumber};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use openssl::sha::sha256;
 use serde::{Deserialize, Serialize};
 
+const HASH_HEADER: &str = "x-ms-meta-hash";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FetchInvoiceResponse {
     pub content: Vec<u8>,
-    pub hash: String,
+    pub hash: InvoiceHash,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+impl FetchInvoiceResponse {
+    pub fn verify_hash(&self, expected: &InvoiceHash) -> Result<(), KsefError> {
+        if self.hash != *expected {
+            return Err(KsefError::integrity(
+                "invoice content",
+                IntegrityCheck::Hash,
+                expected,
+                &self.hash,
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub fn compute_invoice_hash(content: &[u8]) -> InvoiceHash {
+    InvoiceHash::new(BASE64.encode(sha256(content)))
 }
 
 pub async fn fetch_invoice(
     client: &KsefClient,
     ksef_number: &KsefNumber,
+) -> Result<FetchInvoiceResponse, KsefError> {
+    fetch_invoice_with_hash(client, ksef_number, None).await
+}
+
+pub async fn fetch_invoice_for_metadata(
+    client: &KsefClient,
+    metadata: &InvoiceMetadata,
+) -> Result<FetchInvoiceResponse, KsefError> {
+    let expected = Some(InvoiceHash::new(metadata.invoice_hash.as_str())).filter(|h| !h.is_empty());
+    fetch_invoice_with_hash(
+        client,
+        &KsefNumber::new(metadata.ksef_number.as_str()),
+        expected.as_ref(),
+    )
+    .await
+}
+
+pub async fn fetch_invoice_into_archive(
+    client: &KsefClient,
+    metadata: &InvoiceMetadata,
+    archive: &dyn InvoiceArchive,
+) -> Result<ArchiveEntry, KsefError> {
+    let invoice = fetch_invoice_for_metadata(client, metadata).await?;
+    if !invoice.verified {
+        return Err(KsefError::InvalidResponse(format!(
+            "Invoice {} has no hash from KSeF to verify its content against",
+            metadata.ksef_number
+        )));
+    }
+    let record =
+        ArchiveRecord::with_hash(metadata.ksef_number.as_str(), invoice.content, invoice.hash)
+            .with_metadata(metadata.clone());
+    archive.store(record).await
+}
+
+pub async fn fetch_invoice_with_hash(
+    client: &KsefClient,
+    ksef_number: &KsefNumber,
+    expected_hash: Option<&InvoiceHash>,
 ) -> Result<FetchInvoiceResponse, KsefError> {
     let url = client.url_for(&format!("{}/{}", routes::INVOICES_PATH, ksef_number));
     let http = &client.client;
@@ -34,20 +99,105 @@ pub async fn fetch_invoice(
 
     let status = resp.status();
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
+    }
+
     if !status.is_success() {
         let code = status.as_u16();
         let body = resp.text().await.unwrap_or_default();
         return Err(KsefError::from_api_response(code, body));
     }
 
-    let hash = resp
+    let header_hash = resp
         .headers()
-        .get("x-ms-meta-hash")
+        .get(HASH_HEADER)
         .and_then(|h| h.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_default();
+        .map(|s| InvoiceHash::new(s.trim()))
+        .filter(|h| !h.is_empty());
 
     let content = resp.bytes().await?.to_vec();
 
-    Ok(FetchInvoiceResponse { content, hash })
+    verify_invoice(ksef_number, content, header_hash.as_ref(), expected_hash)
+}
+
+fn verify_invoice(
+    ksef_number: &KsefNumber,
+    content: Vec<u8>,
+    header_hash: Option<&InvoiceHash>,
+    expected_hash: Option<&InvoiceHash>,
+) -> Result<FetchInvoiceResponse, KsefError> {
+    let hash = compute_invoice_hash(&content);
+
+    if let Some(header_hash) = header_hash
+        && *header_hash != hash
+    {
+        return Err(KsefError::integrity(
+            format!("invoice {} ({})", ksef_number, HASH_HEADER),
+            IntegrityCheck::Hash,
+            header_hash,
+            &hash,
+        ));
+    }
+
+    if let Some(expected_hash) = expected_hash
+        && *expected_hash != hash
+    {
+        return Err(KsefError::integrity(
+            format!("invoice {} (metadata)", ksef_number),
+            IntegrityCheck::Hash,
+            expected_hash,
+            &hash,
+        ));
+    }
+
+    Ok(FetchInvoiceResponse {
+        content,
+        hash,
+        verified: header_hash.is_some() || expected_hash.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_invoice() {
+        let ksef_number = KsefNumber::new("5265877635-20250626-010080DD2B5E-26");
+        let content = b"<Faktura/>".to_vec();
+        let hash = compute_invoice_hash(&content);
+        let other = compute_invoice_hash(b"<Faktura></Faktura>");
+
+        let verified = verify_invoice(&ksef_number, content.clone(), Some(&hash), Some(&hash))
+            .expect("Matching hashes should verify");
+        assert_eq!(verified.hash, hash);
+        assert!(verified.verified);
+        assert!(verified.verify_hash(&hash).is_ok());
+        assert!(
+            verify_invoice(&ksef_number, content.clone(), None, Some(&hash))
+                .unwrap()
+                .verified
+        );
+
+        let unchecked = verify_invoice(&ksef_number, content.clone(), None, None).unwrap();
+        assert!(!unchecked.verified);
+        assert_eq!(unchecked.hash, hash);
+
+        match verify_invoice(&ksef_number, content.clone(), Some(&other), None) {
+            Err(KsefError::IntegrityError(mismatch)) => {
+                assert_eq!(mismatch.check, IntegrityCheck::Hash);
+                assert!(mismatch.subject.contains(HASH_HEADER));
+                assert_eq!(mismatch.expected, other.as_str());
+            }
+            other => panic!("expected integrity error, got {:?}", other),
+        }
+
+        match verify_invoice(&ksef_number, content, Some(&hash), Some(&other)) {
+            Err(KsefError::IntegrityError(mismatch)) => {
+                assert!(mismatch.subject.contains("metadata"))
+            }
+            other => panic!("expected integrity error, got {:?}", other),
+        }
+    }
 }
@@ -1,7 +1,9 @@
 use crate::client::KsefClient;
+use crate::client::dates::{end_of_warsaw_day, start_of_warsaw_day};
 use crate::client::error::KsefError;
 use crate::client::routes;
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Clone)]
@@ -222,7 +224,7 @@ impl QueryCriteriaBuilder {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum SubjectType {
     #[serde(rename = "Subject1")]
     Subject1,
@@ -239,9 +241,9 @@ pub struct DateRange {
     #[serde(rename = "dateType")]
     pub date_type: DateType,
     #[serde(rename = "from")]
-    pub from: String,
+    pub from: DateTime<Utc>,
     #[serde(rename = "to", skip_serializing_if = "Option::is_none")]
-    pub to: Option<String>,
+    pub to: Option<DateTime<Utc>>,
     #[serde(
         rename = "restrictToPermanentStorageHwmDate",
         skip_serializing_if = "Option::is_none"
@@ -252,8 +254,8 @@ pub struct DateRange {
 #[derive(Debug, Clone)]
 pub struct DateRangeBuilder {
     date_type: Option<DateType>,
-    from: Option<String>,
-    to: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
     restrict_to_permanent_storage_hwm_date: Option<bool>,
 }
 
@@ -278,16 +280,24 @@ impl DateRangeBuilder {
         self
     }
 
-    pub fn from(mut self, v: impl Into<String>) -> Self {
+    pub fn from(mut self, v: impl Into<DateTime<Utc>>) -> Self {
         self.from = Some(v.into());
         self
     }
 
-    pub fn to(mut self, v: impl Into<String>) -> Self {
+    pub fn to(mut self, v: impl Into<DateTime<Utc>>) -> Self {
         self.to = Some(v.into());
         self
     }
 
+    pub fn from_date(self, date: NaiveDate) -> Self {
+        self.from(start_of_warsaw_day(date))
+    }
+
+    pub fn to_date(self, date: NaiveDate) -> Self {
+        self.to(end_of_warsaw_day(date))
+    }
+
     pub fn restrict_to_permanent_storage_hwm_date(mut self, flag: bool) -> Self {
         self.restrict_to_permanent_storage_hwm_date = Some(flag);
         self
@@ -355,7 +365,7 @@ pub enum BuyerIdentifierType {
     None,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum InvoicingMode {
     #[serde(rename = "Online")]
     Online,
@@ -408,25 +418,25 @@ pub struct FetchInvoiceMetadataResponse {
     #[serde(rename = "isTruncated")]
     pub is_truncated: bool,
     #[serde(rename = "permanentStorageHwmDate")]
-    pub permanent_storage_hwm_date: Option<String>,
+    pub permanent_storage_hwm_date: Option<DateTime<Utc>>,
     #[serde(rename = "invoices")]
     pub invoices: Vec<InvoiceMetadata>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvoiceMetadata {
     #[serde(rename = "ksefNumber")]
     pub ksef_number: String,
     #[serde(rename = "invoiceNumber")]
     pub invoice_number: String,
     #[serde(rename = "issueDate")]
-    pub issue_date: String,
+    pub issue_date: NaiveDate,
     #[serde(rename = "invoicingDate")]
-    pub invoicing_date: String,
+    pub invoicing_date: DateTime<Utc>,
     #[serde(rename = "acquisitionDate")]
-    pub acquisition_date: Option<String>,
+    pub acquisition_date: Option<DateTime<Utc>>,
     #[serde(rename = "permanentStorageDate")]
-    pub permanent_storage_date: Option<String>,
+    pub permanent_storage_date: Option<DateTime<Utc>>,
     #[serde(rename = "seller")]
     pub seller: SellerMetadata,
     #[serde(rename = "buyer")]
@@ -459,7 +469,7 @@ pub struct InvoiceMetadata {
     pub authorized_subject: Option<AuthorizedSubjectMetadata>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SellerMetadata {
     #[serde(rename = "nip")]
     pub nip: String,
@@ -467,7 +477,7 @@ pub struct SellerMetadata {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuyerMetadata {
     #[serde(rename = "identifier")]
     pub identifier: BuyerIdentifierMetadata,
@@ -475,7 +485,7 @@ pub struct BuyerMetadata {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuyerIdentifierMetadata {
     #[serde(rename = "type")]
     pub identifier_type: BuyerIdentifierType,
@@ -483,7 +493,7 @@ pub struct BuyerIdentifierMetadata {
     pub value: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvoiceFormCode {
     #[serde(rename = "systemCode")]
     pub system_code: String,
@@ -493,7 +503,7 @@ pub struct InvoiceFormCode {
     pub value: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThirdSubjectMetadata {
     #[serde(rename = "identifier")]
     pub identifier: ThirdSubjectIdentifier,
@@ -503,7 +513,7 @@ pub struct ThirdSubjectMetadata {
     pub role: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThirdSubjectIdentifier {
     #[serde(rename = "type")]
     pub identifier_type: String, // Enum: "Nip" "InternalId" "VatUe" "Other" "None"
@@ -511,7 +521,7 @@ pub struct ThirdSubjectIdentifier {
     pub value: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorizedSubjectMetadata {
     #[serde(rename = "nip")]
     pub nip: String,
@@ -552,6 +562,10 @@ pub async fn fetch_invoice_metadata(
 
     let status = resp.status();
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
+    }
+
     if !status.is_success() {
         let code = status.as_u16();
         let body = resp.text().await.unwrap_or_default();
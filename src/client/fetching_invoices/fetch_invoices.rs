@@ -0,0 +1,212 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::fetching_invoices::fetch_invoice::{compute_invoice_hash, fetch_invoice};
+use crate::client::fs::write_atomically;
+use crate::client::retry::RetryPolicy;
+use crate::client::types::{InvoiceHash, KsefNumber};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct FetchInvoicesOptions {
+    pub concurrency: usize,
+    pub retry_policy: RetryPolicy,
+    pub output_dir: Option<PathBuf>,
+}
+
+impl Default for FetchInvoicesOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+            output_dir: None,
+        }
+    }
+}
+
+impl FetchInvoicesOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(dir.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadedInvoice {
+    pub ksef_number: KsefNumber,
+    pub hash: InvoiceHash,
+    pub content: Vec<u8>,
+    pub path: Option<PathBuf>,
+    pub skipped: bool,
+}
+
+pub type FetchInvoicesItem = (KsefNumber, Result<DownloadedInvoice, KsefError>);
+
+pub fn fetch_invoices(
+    client: &KsefClient,
+    ksef_numbers: Vec<KsefNumber>,
+    concurrency: usize,
+) -> BoxStream<'_, FetchInvoicesItem> {
+    fetch_invoices_with_options(
+        client,
+        ksef_numbers,
+        FetchInvoicesOptions::default().with_concurrency(concurrency),
+    )
+}
+
+pub fn fetch_invoices_with_options(
+    client: &KsefClient,
+    ksef_numbers: Vec<KsefNumber>,
+    options: FetchInvoicesOptions,
+) -> BoxStream<'_, FetchInvoicesItem> {
+    let concurrency = options.concurrency.max(1);
+    let options = Arc::new(options);
+
+    let mut unique = HashSet::new();
+    let ksef_numbers: Vec<KsefNumber> = ksef_numbers
+        .into_iter()
+        .filter(|n| unique.insert(n.clone()))
+        .collect();
+
+    stream::iter(ksef_numbers)
+        .map(move |ksef_number| fetch_one(client, ksef_number, options.clone()))
+        .buffer_unordered(concurrency)
+        .boxed()
+}
+
+async fn fetch_one(
+    client: &KsefClient,
+    ksef_number: KsefNumber,
+    options: Arc<FetchInvoicesOptions>,
+) -> FetchInvoicesItem {
+    let result = fetch_and_store(client, &ksef_number, &options).await;
+    (ksef_number, result)
+}
+
+async fn fetch_and_store(
+    client: &KsefClient,
+    ksef_number: &KsefNumber,
+    options: &FetchInvoicesOptions,
+) -> Result<DownloadedInvoice, KsefError> {
+    let paths = match &options.output_dir {
+        Some(dir) => Some(InvoicePaths::new(dir, ksef_number)?),
+        None => None,
+    };
+
+    if let Some(paths) = &paths
+        && let Some((content, hash)) = paths.read_verified().await?
+    {
+        return Ok(DownloadedInvoice {
+            ksef_number: ksef_number.clone(),
+            hash,
+            content,
+            path: Some(paths.xml.clone()),
+            skipped: true,
+        });
+    }
+
+    let invoice = options
+        .retry_policy
+        .run(|| fetch_invoice(client, ksef_number))
+        .await?;
+
+    if let Some(paths) = &paths {
+        paths.write(&invoice.content, &invoice.hash).await?;
+    }
+
+    Ok(DownloadedInvoice {
+        ksef_number: ksef_number.clone(),
+        hash: invoice.hash,
+        content: invoice.content,
+        path: paths.map(|p| p.xml),
+        skipped: false,
+    })
+}
+
+struct InvoicePaths {
+    xml: PathBuf,
+    hash: PathBuf,
+}
+
+impl InvoicePaths {
+    fn new(dir: &Path, ksef_number: &KsefNumber) -> Result<Self, KsefError> {
+        if ksef_number.is_empty()
+            || ksef_number.contains(['/', '\\'])
+            || ksef_number.starts_with('.')
+        {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!("Invalid KSeF number for a file name: {}", ksef_number),
+            ));
+        }
+
+        Ok(Self {
+            xml: dir.join(format!("{}.xml", ksef_number)),
+            hash: dir.join(format!("{}.sha256", ksef_number)),
+        })
+    }
+
+    async fn read_verified(&self) -> Result<Option<(Vec<u8>, InvoiceHash)>, KsefError> {
+        if !tokio::fs::try_exists(&self.xml).await? || !tokio::fs::try_exists(&self.hash).await? {
+            return Ok(None);
+        }
+
+        let content = tokio::fs::read(&self.xml).await?;
+        let stored = tokio::fs::read_to_string(&self.hash).await?;
+        let hash = compute_invoice_hash(&content);
+        if stored.trim() != hash.as_str() {
+            return Ok(None);
+        }
+        Ok(Some((content, hash)))
+    }
+
+    async fn write(&self, content: &[u8], hash: &InvoiceHash) -> Result<(), KsefError> {
+        write_atomically(&self.xml, content).await?;
+        write_atomically(&self.hash, hash.as_bytes()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_invoice_paths_verification() {
+        let dir = std::env::temp_dir().join(format!("ksef-fetch-invoices-{}", std::process::id()));
+        let ksef_number = KsefNumber::new("5265877635-20250626-010080DD2B5E-26");
+        let paths = InvoicePaths::new(&dir, &ksef_number).unwrap();
+        assert!(paths.read_verified().await.unwrap().is_none());
+
+        let content = b"<Faktura/>";
+        let hash = compute_invoice_hash(content);
+        paths.write(content, &hash).await.unwrap();
+        let (read, read_hash) = paths.read_verified().await.unwrap().unwrap();
+        assert_eq!(read, content);
+        assert_eq!(read_hash, hash);
+
+        tokio::fs::write(&paths.xml, b"<Faktura>changed</Faktura>")
+            .await
+            .unwrap();
+        assert!(paths.read_verified().await.unwrap().is_none());
+
+        assert!(InvoicePaths::new(&dir, &KsefNumber::new("../escape")).is_err());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -1,17 +1,65 @@
 use crate::client::KsefClient;
+use crate::client::archive::store::{ArchiveRecord, InvoiceArchive};
 use crate::client::error::KsefError;
-use crate::client::fetching_invoices::export_invoices::{ExportResult, InvoicePackageMetadata};
+use crate::client::fetching_invoices::export_invoices::{ExportResult, ExportWarning};
 use crate::client::fetching_invoices::fetch_invoice_metadata::{
     DateRangeBuilder, DateType, InvoiceMetadata, QueryCriteriaBuilder, SubjectType,
 };
+use crate::client::fetching_invoices::state_store::{SeenStore, StateStore};
 use crate::client::traits::*;
-use chrono::{DateTime, Utc};
-use std::collections::{HashMap, HashSet};
-use std::io::Read;
+use crate::client::types::KsefNumber;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+pub const INCREMENTAL_FETCH_STATE_VERSION: u32 = 1;
 
 #[derive(Debug, Clone)]
+pub struct IncrementalFetchOptions {
+    pub window_end: Option<DateTime<Utc>>,
+    pub overlap: Duration,
+}
+
+impl Default for IncrementalFetchOptions {
+    fn default() -> Self {
+        Self {
+            window_end: None,
+            overlap: Duration::minutes(5),
+        }
+    }
+}
+
+impl IncrementalFetchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_window_end(mut self, window_end: DateTime<Utc>) -> Self {
+        self.window_end = Some(window_end);
+        self
+    }
+
+    pub fn with_overlap(mut self, overlap: Duration) -> Self {
+        self.overlap = overlap;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuationPoint {
+    pub date: DateTime<Utc>,
+    pub last_run_at: DateTime<Utc>,
+    pub last_invoice_count: i64,
+    pub last_run_truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct IncrementalFetchState {
-    pub continuation_points: HashMap<String, DateTime<Utc>>,
+    pub version: u32,
+    pub continuation_points: BTreeMap<SubjectType, ContinuationPoint>,
 }
 
 impl Default for IncrementalFetchState {
@@ -23,47 +71,81 @@ impl Default for IncrementalFetchState {
 impl IncrementalFetchState {
     pub fn new() -> Self {
         Self {
-            continuation_points: HashMap::new(),
+            version: INCREMENTAL_FETCH_STATE_VERSION,
+            continuation_points: BTreeMap::new(),
         }
     }
 
+    pub fn from_json(json: &[u8]) -> Result<Self, KsefError> {
+        let state: Self = serde_json::from_slice(json)?;
+        if state.version > INCREMENTAL_FETCH_STATE_VERSION {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Unsupported incremental fetch state version {} (supported up to {})",
+                    state.version, INCREMENTAL_FETCH_STATE_VERSION
+                ),
+            ));
+        }
+        Ok(state)
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>, KsefError> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
     pub fn get_start_date(
         &self,
         subject_type: &SubjectType,
         default_start: DateTime<Utc>,
     ) -> DateTime<Utc> {
-        let key = format!("{:?}", subject_type);
         self.continuation_points
-            .get(&key)
-            .cloned()
+            .get(subject_type)
+            .map(|point| point.date)
             .unwrap_or(default_start)
     }
 
+    pub fn get_start_date_with_overlap(
+        &self,
+        subject_type: &SubjectType,
+        default_start: DateTime<Utc>,
+        overlap: Duration,
+    ) -> DateTime<Utc> {
+        match self.continuation_points.get(subject_type) {
+            Some(point) if !point.last_run_truncated => point.date - overlap,
+            Some(point) => point.date,
+            None => default_start,
+        }
+    }
+
     pub fn update_continuation_point(
         &mut self,
         subject_type: &SubjectType,
         export_result: &ExportResult,
     ) {
-        let key = format!("{:?}", subject_type);
         let package = if let Some(pkg) = &export_result.status.package {
             pkg
         } else {
             return;
         };
 
-        if package.is_truncated {
-            if let Some(last_date_str) = &package.last_permanent_storage_date {
-                if let Ok(date) = DateTime::parse_from_rfc3339(last_date_str) {
-                    self.continuation_points
-                        .insert(key, date.with_timezone(&Utc));
-                }
-            }
-        } else if let Some(hwm_date_str) = &package.permanent_storage_hwm_date {
-            if let Ok(date) = DateTime::parse_from_rfc3339(hwm_date_str) {
-                self.continuation_points
-                    .insert(key, date.with_timezone(&Utc));
-            }
+        let date = if package.is_truncated {
+            package.last_permanent_storage_date
         } else {
+            package.permanent_storage_hwm_date
+        };
+        let date = date.or_else(|| self.continuation_points.get(subject_type).map(|p| p.date));
+
+        if let Some(date) = date {
+            self.continuation_points.insert(
+                subject_type.clone(),
+                ContinuationPoint {
+                    date,
+                    last_run_at: Utc::now(),
+                    last_invoice_count: package.invoice_count,
+                    last_run_truncated: package.is_truncated,
+                },
+            );
         }
     }
 }
@@ -74,117 +156,450 @@ pub struct FetchedInvoice {
     pub content: String,
 }
 
+#[derive(Debug, Default)]
+pub struct FetchedInvoices {
+    invoices: Vec<FetchedInvoice>,
+    warnings: Vec<ExportWarning>,
+}
+
+impl FetchedInvoices {
+    pub fn len(&self) -> usize {
+        self.invoices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.invoices.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FetchedInvoice> {
+        self.invoices.iter()
+    }
+
+    pub fn warnings(&self) -> &[ExportWarning] {
+        &self.warnings
+    }
+
+    pub fn into_parts(self) -> (Vec<FetchedInvoice>, Vec<ExportWarning>) {
+        (self.invoices, self.warnings)
+    }
+}
+
+impl IntoIterator for FetchedInvoices {
+    type Item = FetchedInvoice;
+    type IntoIter = std::vec::IntoIter<FetchedInvoice>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.invoices.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FetchedInvoices {
+    type Item = &'a FetchedInvoice;
+    type IntoIter = std::slice::Iter<'a, FetchedInvoice>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.invoices.iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct IncrementalArchiveResult {
+    pub state: IncrementalFetchState,
+    pub warnings: Vec<ExportWarning>,
+}
+
 pub async fn fetch_invoices_incrementally(
     client: &KsefClient,
     state: &mut IncrementalFetchState,
     subject_types: Vec<SubjectType>,
-    window_end: Option<DateTime<Utc>>,
     default_start: DateTime<Utc>,
-) -> Result<Vec<FetchedInvoice>, KsefError> {
-    let mut all_fetched_invoices = Vec::new();
-    let mut processed_ksef_numbers = HashSet::new();
+    options: &IncrementalFetchOptions,
+) -> Result<FetchedInvoices, KsefError> {
+    let mut sink = CollectSink::default();
+    let sources = FetchSources {
+        store: None,
+        seen: None,
+    };
+    run_incremental_fetch(
+        client,
+        state,
+        sources,
+        subject_types,
+        default_start,
+        options,
+        &mut sink,
+    )
+    .await?;
+    Ok(sink.fetched)
+}
 
-    for subject_type in subject_types {
-        let start_date = state.get_start_date(&subject_type, default_start);
+pub async fn fetch_invoices_incrementally_with_store<H>(
+    client: &KsefClient,
+    store: &dyn StateStore,
+    subject_types: Vec<SubjectType>,
+    default_start: DateTime<Utc>,
+    options: &IncrementalFetchOptions,
+    handler: H,
+) -> Result<IncrementalFetchState, KsefError>
+where
+    H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send,
+{
+    let mut state = store.load().await?.unwrap_or_default();
+    let sources = FetchSources {
+        store: Some(store),
+        seen: None,
+    };
+    run_incremental_fetch(
+        client,
+        &mut state,
+        sources,
+        subject_types,
+        default_start,
+        options,
+        &mut HandlerSink(handler),
+    )
+    .await?;
+    Ok(state)
+}
 
-        if let Some(end) = window_end {
-            if start_date >= end {
-                continue;
-            }
-        }
+pub async fn fetch_invoices_incrementally_with_options<H>(
+    client: &KsefClient,
+    store: &dyn StateStore,
+    seen: &dyn SeenStore,
+    subject_types: Vec<SubjectType>,
+    default_start: DateTime<Utc>,
+    options: &IncrementalFetchOptions,
+    handler: H,
+) -> Result<IncrementalFetchState, KsefError>
+where
+    H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send,
+{
+    let mut state = store.load().await?.unwrap_or_default();
+    let sources = FetchSources {
+        store: Some(store),
+        seen: Some(seen),
+    };
+    run_incremental_fetch(
+        client,
+        &mut state,
+        sources,
+        subject_types,
+        default_start,
+        options,
+        &mut HandlerSink(handler),
+    )
+    .await?;
+    Ok(state)
+}
+
+pub async fn fetch_invoices_incrementally_into_archive(
+    client: &KsefClient,
+    store: &dyn StateStore,
+    archive: &dyn InvoiceArchive,
+    subject_types: Vec<SubjectType>,
+    default_start: DateTime<Utc>,
+    options: &IncrementalFetchOptions,
+) -> Result<IncrementalArchiveResult, KsefError> {
+    let mut state = store.load().await?.unwrap_or_default();
+    let seen = ArchiveSeenStore(archive);
+    let sources = FetchSources {
+        store: Some(store),
+        seen: Some(&seen),
+    };
+    let mut sink = ArchiveSink {
+        archive,
+        warnings: Vec::new(),
+    };
+    run_incremental_fetch(
+        client,
+        &mut state,
+        sources,
+        subject_types,
+        default_start,
+        options,
+        &mut sink,
+    )
+    .await?;
+    Ok(IncrementalArchiveResult {
+        state,
+        warnings: sink.warnings,
+    })
+}
+
+struct FetchSources<'a> {
+    store: Option<&'a dyn StateStore>,
+    seen: Option<&'a dyn SeenStore>,
+}
+
+#[async_trait]
+trait InvoiceSink: Send {
+    async fn deliver(
+        &mut self,
+        subject_type: &SubjectType,
+        invoices: FetchedInvoices,
+    ) -> Result<(), KsefError>;
+}
+
+struct HandlerSink<H>(H);
+
+#[async_trait]
+impl<H> InvoiceSink for HandlerSink<H>
+where
+    H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send,
+{
+    async fn deliver(
+        &mut self,
+        subject_type: &SubjectType,
+        invoices: FetchedInvoices,
+    ) -> Result<(), KsefError> {
+        (self.0)(subject_type, invoices)
+    }
+}
+
+#[derive(Default)]
+struct CollectSink {
+    fetched: FetchedInvoices,
+    ksef_numbers: HashSet<String>,
+}
 
-        let mut dr_builder = DateRangeBuilder::new()
-            .date_type(DateType::PermanentStorage)
-            .from(start_date.to_rfc3339())
-            .restrict_to_permanent_storage_hwm_date(true);
+#[async_trait]
+impl InvoiceSink for CollectSink {
+    async fn deliver(
+        &mut self,
+        _subject_type: &SubjectType,
+        invoices: FetchedInvoices,
+    ) -> Result<(), KsefError> {
+        let (invoices, warnings) = invoices.into_parts();
+        self.fetched
+            .invoices
+            .extend(invoices.into_iter().filter(|invoice| {
+                self.ksef_numbers
+                    .insert(invoice.metadata.ksef_number.clone())
+            }));
+        self.fetched.warnings.extend(warnings);
+        Ok(())
+    }
+}
+
+struct ArchiveSink<'a> {
+    archive: &'a dyn InvoiceArchive,
+    warnings: Vec<ExportWarning>,
+}
 
-        if let Some(end_dt) = window_end.as_ref() {
-            dr_builder = dr_builder.to(end_dt.to_rfc3339());
+#[async_trait]
+impl InvoiceSink for ArchiveSink<'_> {
+    async fn deliver(
+        &mut self,
+        _subject_type: &SubjectType,
+        invoices: FetchedInvoices,
+    ) -> Result<(), KsefError> {
+        let (invoices, warnings) = invoices.into_parts();
+        self.warnings.extend(warnings);
+        for invoice in invoices {
+            self.archive.store(ArchiveRecord::from(invoice)).await?;
         }
+        Ok(())
+    }
+}
 
-        let date_range = dr_builder.build().map_err(|e| {
-            KsefError::ApplicationError(0, format!("Failed to build DateRange: {}", e))
-        })?;
+struct ArchiveSeenStore<'a>(&'a dyn InvoiceArchive);
+
+#[async_trait]
+impl SeenStore for ArchiveSeenStore<'_> {
+    async fn contains(
+        &self,
+        _subject_type: &SubjectType,
+        ksef_number: &str,
+    ) -> Result<bool, KsefError> {
+        self.0.contains(&KsefNumber::new(ksef_number)).await
+    }
+
+    async fn insert(
+        &self,
+        _subject_type: &SubjectType,
+        _invoices: &[(String, DateTime<Utc>)],
+    ) -> Result<(), KsefError> {
+        Ok(())
+    }
+
+    async fn prune(
+        &self,
+        _subject_type: &SubjectType,
+        _before: DateTime<Utc>,
+    ) -> Result<(), KsefError> {
+        Ok(())
+    }
+}
+
+async fn run_incremental_fetch(
+    client: &KsefClient,
+    state: &mut IncrementalFetchState,
+    sources: FetchSources<'_>,
+    subject_types: Vec<SubjectType>,
+    default_start: DateTime<Utc>,
+    options: &IncrementalFetchOptions,
+    sink: &mut dyn InvoiceSink,
+) -> Result<(), KsefError> {
+    let overlap = options.overlap;
 
-        let query = QueryCriteriaBuilder::new()
-            .subject_type(subject_type.clone())
-            .date_range(date_range)
-            .build()
-            .map_err(|e| {
-                KsefError::ApplicationError(0, format!("Failed to build QueryCriteria: {}", e))
-            })?;
-
-        let export_result = client.export_invoices(query).await?;
-
-        let mut invoices_in_batch = HashMap::new();
-        let mut metadata_in_batch = HashMap::new();
-
-        for part in &export_result.parts {
-            let cursor = std::io::Cursor::new(&part.content);
-            let mut archive = zip::ZipArchive::new(cursor).map_err(|e| {
-                KsefError::ApplicationError(0, format!("Failed to open zip archive: {}", e))
-            })?;
-
-            for i in 0..archive.len() {
-                let mut file = archive.by_index(i).map_err(|e| {
-                    KsefError::ApplicationError(0, format!("Failed to read file in zip: {}", e))
-                })?;
-
-                if file.name().ends_with(".xml") {
-                    let mut xml_content = String::new();
-                    file.read_to_string(&mut xml_content).map_err(|e| {
-                        KsefError::ApplicationError(0, format!("Failed to read xml content: {}", e))
-                    })?;
-                    invoices_in_batch.insert(file.name().to_string(), xml_content);
-                } else if file.name().ends_with(".json") && file.name().contains("metadata") {
-                    let mut json_content = String::new();
-                    file.read_to_string(&mut json_content).map_err(|e| {
-                        KsefError::ApplicationError(
-                            0,
-                            format!("Failed to read metadata json: {}", e),
-                        )
-                    })?;
-
-                    let metadata_pkg: InvoicePackageMetadata = serde_json::from_str(&json_content)
-                        .map_err(|e| {
-                            KsefError::ApplicationError(
-                                0,
-                                format!("Failed to parse metadata json: {}", e),
-                            )
-                        })?;
-
-                    for meta in metadata_pkg.invoices {
-                        metadata_in_batch.insert(meta.ksef_number.clone(), meta);
-                    }
+    for subject_type in subject_types {
+        let start_date = state.get_start_date_with_overlap(&subject_type, default_start, overlap);
+        let Some(export_result) =
+            export_subject(client, &subject_type, start_date, options.window_end).await?
+        else {
+            continue;
+        };
+
+        let mut fetched = unpack_export(&export_result)?;
+        if let Some(seen) = sources.seen {
+            let mut fresh = Vec::with_capacity(fetched.invoices.len());
+            for invoice in fetched.invoices {
+                if !seen
+                    .contains(&subject_type, &invoice.metadata.ksef_number)
+                    .await?
+                {
+                    fresh.push(invoice);
                 }
             }
+            fetched.invoices = fresh;
         }
 
-        for (ksef_number, meta) in metadata_in_batch {
-            if processed_ksef_numbers.contains(&ksef_number) {
-                continue;
-            }
+        let seen_entries: Vec<(String, DateTime<Utc>)> = fetched
+            .iter()
+            .map(|invoice| {
+                (
+                    invoice.metadata.ksef_number.clone(),
+                    seen_date(&invoice.metadata),
+                )
+            })
+            .collect();
 
-            let content = invoices_in_batch
-                .iter()
-                .find(|(name, _)| name.contains(&ksef_number));
-
-            if let Some((_, xml)) = content {
-                processed_ksef_numbers.insert(ksef_number.clone());
-                all_fetched_invoices.push(FetchedInvoice {
-                    metadata: meta,
-                    content: xml.clone(),
-                });
-            } else {
-                println!(
-                    "Warning: Content for KSeF number {} not found in zip parts",
-                    ksef_number
-                );
-            }
+        // Delivery is at-least-once: without a seen store the overlap is delivered
+        // again, and a stop before `insert` repeats the whole batch on the next run.
+        sink.deliver(&subject_type, fetched).await?;
+        if let Some(seen) = sources.seen {
+            seen.insert(&subject_type, &seen_entries).await?;
         }
 
         state.update_continuation_point(&subject_type, &export_result);
+        if let Some(store) = sources.store {
+            store.save(state).await?;
+        }
+
+        if let Some(seen) = sources.seen
+            && let Some(point) = state.continuation_points.get(&subject_type)
+        {
+            seen.prune(&subject_type, point.date - overlap).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn seen_date(metadata: &InvoiceMetadata) -> DateTime<Utc> {
+    metadata.permanent_storage_date.unwrap_or_else(Utc::now)
+}
+
+async fn export_subject(
+    client: &KsefClient,
+    subject_type: &SubjectType,
+    start_date: DateTime<Utc>,
+    window_end: Option<DateTime<Utc>>,
+) -> Result<Option<ExportResult>, KsefError> {
+    if let Some(end) = window_end
+        && start_date >= end
+    {
+        return Ok(None);
     }
 
-    Ok(all_fetched_invoices)
+    let mut dr_builder = DateRangeBuilder::new()
+        .date_type(DateType::PermanentStorage)
+        .from(start_date)
+        .restrict_to_permanent_storage_hwm_date(true);
+
+    if let Some(end_dt) = window_end.as_ref() {
+        dr_builder = dr_builder.to(*end_dt);
+    }
+
+    let date_range = dr_builder
+        .build()
+        .map_err(|e| KsefError::ApplicationError(0, format!("Failed to build DateRange: {}", e)))?;
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(subject_type.clone())
+        .date_range(date_range)
+        .build()
+        .map_err(|e| {
+            KsefError::ApplicationError(0, format!("Failed to build QueryCriteria: {}", e))
+        })?;
+
+    Ok(Some(client.export_invoices(query).await?))
+}
+
+fn unpack_export(export_result: &ExportResult) -> Result<FetchedInvoices, KsefError> {
+    let invoices = export_result.invoices()?;
+    let mut processed_ksef_numbers = HashSet::new();
+    let mut fetched_invoices = FetchedInvoices {
+        invoices: Vec::new(),
+        warnings: invoices.warnings().to_vec(),
+    };
+
+    for (meta, content) in invoices {
+        if processed_ksef_numbers.contains(&meta.ksef_number) {
+            continue;
+        }
+
+        let content = String::from_utf8(content).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Failed to read xml content: {}", e))
+        })?;
+        processed_ksef_numbers.insert(meta.ksef_number.clone());
+        fetched_invoices.invoices.push(FetchedInvoice {
+            metadata: meta,
+            content,
+        });
+    }
+
+    Ok(fetched_invoices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_start_date_with_overlap() {
+        let default_start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let date = Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap();
+        let overlap = Duration::minutes(5);
+
+        let mut state = IncrementalFetchState::new();
+        assert_eq!(
+            state.get_start_date_with_overlap(&SubjectType::Subject1, default_start, overlap),
+            default_start
+        );
+
+        let mut point = ContinuationPoint {
+            date,
+            last_run_at: date,
+            last_invoice_count: 10,
+            last_run_truncated: false,
+        };
+        state
+            .continuation_points
+            .insert(SubjectType::Subject1, point.clone());
+        assert_eq!(
+            state.get_start_date_with_overlap(&SubjectType::Subject1, default_start, overlap),
+            date - overlap
+        );
+
+        point.last_run_truncated = true;
+        state
+            .continuation_points
+            .insert(SubjectType::Subject1, point);
+        assert_eq!(
+            state.get_start_date_with_overlap(&SubjectType::Subject1, default_start, overlap),
+            date
+        );
+    }
 }
@@ -1,4 +1,10 @@
+pub mod date_range_split;
+pub mod download_export;
+pub mod export_handle;
 pub mod export_invoices;
 pub mod fetch_invoice;
 pub mod fetch_invoice_metadata;
+pub mod fetch_invoices;
 pub mod incremental_fetch;
+pub mod state_store;
+pub mod stream_invoice_metadata;
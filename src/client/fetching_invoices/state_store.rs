@@ -0,0 +1,294 @@
+use crate::client::error::KsefError;
+use crate::client::fetching_invoices::fetch_invoice_metadata::SubjectType;
+use crate::client::fetching_invoices::incremental_fetch::IncrementalFetchState;
+use crate::client::fs::write_atomically;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    async fn load(&self) -> Result<Option<IncrementalFetchState>, KsefError>;
+
+    async fn save(&self, state: &IncrementalFetchState) -> Result<(), KsefError>;
+}
+
+#[async_trait]
+pub trait SeenStore: Send + Sync {
+    async fn contains(
+        &self,
+        subject_type: &SubjectType,
+        ksef_number: &str,
+    ) -> Result<bool, KsefError>;
+
+    async fn insert(
+        &self,
+        subject_type: &SubjectType,
+        invoices: &[(String, DateTime<Utc>)],
+    ) -> Result<(), KsefError>;
+
+    async fn prune(
+        &self,
+        subject_type: &SubjectType,
+        before: DateTime<Utc>,
+    ) -> Result<(), KsefError>;
+}
+
+type SeenEntries = BTreeMap<SubjectType, BTreeMap<String, DateTime<Utc>>>;
+
+#[derive(Debug, Default)]
+pub struct MemorySeenStore {
+    entries: Mutex<SeenEntries>,
+}
+
+impl MemorySeenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SeenStore for MemorySeenStore {
+    async fn contains(
+        &self,
+        subject_type: &SubjectType,
+        ksef_number: &str,
+    ) -> Result<bool, KsefError> {
+        let entries = self.entries.lock().await;
+        Ok(entries
+            .get(subject_type)
+            .is_some_and(|seen| seen.contains_key(ksef_number)))
+    }
+
+    async fn insert(
+        &self,
+        subject_type: &SubjectType,
+        invoices: &[(String, DateTime<Utc>)],
+    ) -> Result<(), KsefError> {
+        let mut entries = self.entries.lock().await;
+        insert_entries(&mut entries, subject_type, invoices);
+        Ok(())
+    }
+
+    async fn prune(
+        &self,
+        subject_type: &SubjectType,
+        before: DateTime<Utc>,
+    ) -> Result<(), KsefError> {
+        let mut entries = self.entries.lock().await;
+        prune_entries(&mut entries, subject_type, before);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileSeenStore {
+    path: PathBuf,
+    entries: Mutex<Option<SeenEntries>>,
+}
+
+impl FileSeenStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            entries: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn load(&self) -> Result<SeenEntries, KsefError> {
+        if !tokio::fs::try_exists(&self.path).await? {
+            return Ok(SeenEntries::new());
+        }
+        let content = tokio::fs::read(&self.path).await?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    async fn update<F>(&self, f: F) -> Result<(), KsefError>
+    where
+        F: FnOnce(&mut SeenEntries) + Send,
+    {
+        let mut guard = self.entries.lock().await;
+        let mut entries = match guard.take() {
+            Some(entries) => entries,
+            None => self.load().await?,
+        };
+        f(&mut entries);
+        write_atomically(&self.path, &serde_json::to_vec(&entries)?).await?;
+        *guard = Some(entries);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SeenStore for FileSeenStore {
+    async fn contains(
+        &self,
+        subject_type: &SubjectType,
+        ksef_number: &str,
+    ) -> Result<bool, KsefError> {
+        let mut guard = self.entries.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load().await?);
+        }
+        Ok(guard
+            .as_ref()
+            .and_then(|entries| entries.get(subject_type))
+            .is_some_and(|seen| seen.contains_key(ksef_number)))
+    }
+
+    async fn insert(
+        &self,
+        subject_type: &SubjectType,
+        invoices: &[(String, DateTime<Utc>)],
+    ) -> Result<(), KsefError> {
+        self.update(|entries| insert_entries(entries, subject_type, invoices))
+            .await
+    }
+
+    async fn prune(
+        &self,
+        subject_type: &SubjectType,
+        before: DateTime<Utc>,
+    ) -> Result<(), KsefError> {
+        self.update(|entries| prune_entries(entries, subject_type, before))
+            .await
+    }
+}
+
+fn insert_entries(
+    entries: &mut SeenEntries,
+    subject_type: &SubjectType,
+    invoices: &[(String, DateTime<Utc>)],
+) {
+    let seen = entries.entry(subject_type.clone()).or_default();
+    for (ksef_number, date) in invoices {
+        seen.insert(ksef_number.clone(), *date);
+    }
+}
+
+fn prune_entries(entries: &mut SeenEntries, subject_type: &SubjectType, before: DateTime<Utc>) {
+    if let Some(seen) = entries.get_mut(subject_type) {
+        seen.retain(|_, date| *date >= before);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    path: PathBuf,
+}
+
+impl FileStateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl StateStore for FileStateStore {
+    async fn load(&self) -> Result<Option<IncrementalFetchState>, KsefError> {
+        if !tokio::fs::try_exists(&self.path).await? {
+            return Ok(None);
+        }
+        let content = tokio::fs::read(&self.path).await?;
+        Ok(Some(IncrementalFetchState::from_json(&content)?))
+    }
+
+    async fn save(&self, state: &IncrementalFetchState) -> Result<(), KsefError> {
+        write_atomically(&self.path, &state.to_json()?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fetching_invoices::incremental_fetch::{
+        ContinuationPoint, INCREMENTAL_FETCH_STATE_VERSION,
+    };
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_file_state_store_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("ksef-fetch-state-{}.json", std::process::id()));
+        let store = FileStateStore::new(&path);
+        assert!(store.load().await.unwrap().is_none());
+
+        let mut state = IncrementalFetchState::new();
+        state.continuation_points.insert(
+            SubjectType::Subject2,
+            ContinuationPoint {
+                date: Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap(),
+                last_run_at: Utc.with_ymd_and_hms(2025, 2, 1, 8, 0, 0).unwrap(),
+                last_invoice_count: 42,
+                last_run_truncated: false,
+            },
+        );
+        store.save(&state).await.unwrap();
+
+        let json = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(json.contains("\"Subject2\""));
+
+        let loaded = store.load().await.unwrap().unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(
+            loaded.get_start_date(&SubjectType::Subject2, Utc::now()),
+            Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap()
+        );
+
+        state.version = INCREMENTAL_FETCH_STATE_VERSION + 1;
+        store.save(&state).await.unwrap();
+        assert!(store.load().await.is_err());
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_seen_store_prune_and_reload() {
+        let path =
+            std::env::temp_dir().join(format!("ksef-seen-store-{}.json", std::process::id()));
+        let old = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let new = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+
+        let store = FileSeenStore::new(&path);
+        store
+            .insert(
+                &SubjectType::Subject1,
+                &[("OLD".to_string(), old), ("NEW".to_string(), new)],
+            )
+            .await
+            .unwrap();
+        assert!(store.contains(&SubjectType::Subject1, "OLD").await.unwrap());
+        assert!(!store.contains(&SubjectType::Subject2, "OLD").await.unwrap());
+
+        store
+            .prune(&SubjectType::Subject1, old + chrono::Duration::days(1))
+            .await
+            .unwrap();
+
+        let reloaded = FileSeenStore::new(&path);
+        assert!(
+            !reloaded
+                .contains(&SubjectType::Subject1, "OLD")
+                .await
+                .unwrap()
+        );
+        assert!(
+            reloaded
+                .contains(&SubjectType::Subject1, "NEW")
+                .await
+                .unwrap()
+        );
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}
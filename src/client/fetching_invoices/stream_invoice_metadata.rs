@@ -0,0 +1,148 @@
+use crate::client::KsefClient;
+use crate::client::dates::start_of_warsaw_day;
+use crate::client::error::KsefError;
+use crate::client::fetching_invoices::fetch_invoice_metadata::{
+    DateType, FetchInvoiceMetadataRequest, InvoiceMetadata, QueryCriteria, fetch_invoice_metadata,
+};
+use crate::client::retry::RetryPolicy;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::{HashSet, VecDeque};
+
+pub const METADATA_PAGE_SIZE: i32 = 250;
+
+struct MetadataStreamState {
+    query: QueryCriteria,
+    page_offset: i32,
+    buffer: VecDeque<InvoiceMetadata>,
+    seen: HashSet<String>,
+    done: bool,
+}
+
+pub fn stream_invoice_metadata(
+    client: &KsefClient,
+    query: QueryCriteria,
+) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>> {
+    let state = MetadataStreamState {
+        query,
+        page_offset: 0,
+        buffer: VecDeque::new(),
+        seen: HashSet::new(),
+        done: false,
+    };
+
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(invoice) = state.buffer.pop_front() {
+                return Some((Ok(invoice), state));
+            }
+            if state.done {
+                return None;
+            }
+
+            if let Err(e) = fetch_next_page(client, &mut state).await {
+                state.done = true;
+                return Some((Err(e), state));
+            }
+        }
+    })
+    .boxed()
+}
+
+async fn fetch_next_page(
+    client: &KsefClient,
+    state: &mut MetadataStreamState,
+) -> Result<(), KsefError> {
+    let request = FetchInvoiceMetadataRequest {
+        query: state.query.clone(),
+        page_offset: Some(state.page_offset),
+        page_size: Some(METADATA_PAGE_SIZE),
+    };
+
+    let page = RetryPolicy::default()
+        .run(|| fetch_invoice_metadata(client, request.clone()))
+        .await?;
+
+    let continuation = page
+        .invoices
+        .last()
+        .and_then(|invoice| continuation_from(&state.query.date_range.date_type, invoice));
+
+    for invoice in page.invoices {
+        if state.seen.insert(invoice.ksef_number.clone()) {
+            state.buffer.push_back(invoice);
+        }
+    }
+
+    if page.has_more {
+        state.page_offset += 1;
+    } else if page.is_truncated {
+        let from = continuation.ok_or_else(|| {
+            KsefError::InvalidResponse(
+                "Truncated metadata page has no date to continue from".to_string(),
+            )
+        })?;
+        if from == state.query.date_range.from {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Too many invoices with the same date ({}) to page through; narrow the query",
+                    from
+                ),
+            ));
+        }
+        state.query.date_range.from = from;
+        state.page_offset = 0;
+    } else {
+        state.done = true;
+    }
+
+    Ok(())
+}
+
+fn continuation_from(date_type: &DateType, invoice: &InvoiceMetadata) -> Option<DateTime<Utc>> {
+    match date_type {
+        DateType::Issue => Some(start_of_warsaw_day(invoice.issue_date)),
+        DateType::Invoicing => Some(invoice.invoicing_date),
+        DateType::PermanentStorage => invoice.permanent_storage_date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continuation_from() {
+        let invoice: InvoiceMetadata = serde_json::from_value(serde_json::json!({
+            "ksefNumber": "5265877635-20250626-010080DD2B5E-26",
+            "invoiceNumber": "FA/1/2025",
+            "issueDate": "2025-06-25",
+            "invoicingDate": "2025-06-26T10:00:00.123+00:00",
+            "permanentStorageDate": null,
+            "seller": { "nip": "5265877635" },
+            "buyer": { "identifier": { "type": "None" } },
+            "currency": "PLN",
+            "invoicingMode": "Online",
+            "invoiceType": "Vat",
+            "formCode": { "systemCode": "FA (2)", "schemaVersion": "1-0E", "value": "FA" },
+            "isSelfInvoicing": false,
+            "hasAttachment": false,
+            "invoiceHash": "hash",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            continuation_from(&DateType::Issue, &invoice),
+            Some("2025-06-24T22:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            continuation_from(&DateType::Invoicing, &invoice),
+            Some("2025-06-26T10:00:00.123Z".parse().unwrap())
+        );
+        assert_eq!(
+            continuation_from(&DateType::PermanentStorage, &invoice),
+            None
+        );
+    }
+}
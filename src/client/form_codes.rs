@@ -0,0 +1,206 @@
+use crate::client::error::KsefError;
+use crate::client::models::FormCode;
+use regex::Regex;
+use std::fmt;
+use std::sync::LazyLock;
+
+const FA2_NAMESPACE: &str = "http://crd.gov.pl/wzor/2023/06/29/12648/";
+const FA3_NAMESPACE: &str = "http://crd.gov.pl/wzor/2025/06/25/13775/";
+const UBL_INVOICE_NAMESPACE: &str = "urn:oasis:names:specification:ubl:schema:xsd:Invoice-2";
+const UBL_CREDIT_NOTE_NAMESPACE: &str = "urn:oasis:names:specification:ubl:schema:xsd:CreditNote-2";
+const FORM_CODE_ATTRIBUTE_PATTERN: &str =
+    r#"<(?:[\w.-]+:)?KodFormularza\b[^>]*\bkodSystemowy\s*=\s*(?:"([^"]*)"|'([^']*)')"#;
+
+static ROOT_ELEMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<([^?!/\s>][^\s/>]*)([^>]*)>").unwrap());
+static FORM_CODE_ATTRIBUTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(FORM_CODE_ATTRIBUTE_PATTERN).unwrap());
+static ATTRIBUTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([^\s=/>]+)\s*=\s*(?:'([^']*)'|"([^"]*)")"#).unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InvoiceSchema {
+    Fa2,
+    Fa3,
+    FaRr,
+    Pef,
+    PefCorrection,
+}
+
+impl InvoiceSchema {
+    pub const ALL: [InvoiceSchema; 5] = [
+        InvoiceSchema::Fa2,
+        InvoiceSchema::Fa3,
+        InvoiceSchema::FaRr,
+        InvoiceSchema::Pef,
+        InvoiceSchema::PefCorrection,
+    ];
+
+    pub fn system_code(&self) -> &'static str {
+        match self {
+            InvoiceSchema::Fa2 => "FA (2)",
+            InvoiceSchema::Fa3 => "FA (3)",
+            InvoiceSchema::FaRr => "FA_RR (1)",
+            InvoiceSchema::Pef => "PEF (3)",
+            InvoiceSchema::PefCorrection => "PEF_KOR (3)",
+        }
+    }
+
+    pub fn schema_version(&self) -> &'static str {
+        match self {
+            InvoiceSchema::Fa2 | InvoiceSchema::Fa3 | InvoiceSchema::FaRr => "1-0E",
+            InvoiceSchema::Pef | InvoiceSchema::PefCorrection => "2-1",
+        }
+    }
+
+    pub fn value(&self) -> &'static str {
+        match self {
+            InvoiceSchema::Fa2 | InvoiceSchema::Fa3 => "FA",
+            InvoiceSchema::FaRr => "RR",
+            InvoiceSchema::Pef | InvoiceSchema::PefCorrection => "PEF",
+        }
+    }
+
+    pub fn form_code(&self) -> FormCode {
+        FormCode {
+            system_code: self.system_code().to_string(),
+            schema_version: self.schema_version().to_string(),
+            value: self.value().to_string(),
+        }
+    }
+
+    pub fn from_system_code(system_code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|schema| schema.system_code() == system_code.trim())
+    }
+
+    pub fn detect(invoice_xml: &[u8]) -> Result<Self, KsefError> {
+        let xml = std::str::from_utf8(invoice_xml).map_err(|e| {
+            KsefError::ApplicationError(0, format!("Invoice is not valid UTF-8: {}", e))
+        })?;
+
+        let caps = ROOT_ELEMENT_RE.captures(xml).ok_or_else(|| {
+            KsefError::ApplicationError(0, "Invoice has no root element".to_string())
+        })?;
+
+        let (prefix, local_name) = match caps[1].split_once(':') {
+            Some((prefix, local_name)) => (Some(prefix), local_name),
+            None => (None, &caps[1]),
+        };
+        let namespace = root_namespace(&caps[2], prefix).unwrap_or_default();
+
+        let detected = match (local_name, namespace.as_str()) {
+            ("Faktura", FA2_NAMESPACE) => Some(InvoiceSchema::Fa2),
+            ("Faktura", FA3_NAMESPACE) => Some(InvoiceSchema::Fa3),
+            ("Invoice", UBL_INVOICE_NAMESPACE) => Some(InvoiceSchema::Pef),
+            ("CreditNote", UBL_CREDIT_NOTE_NAMESPACE) => Some(InvoiceSchema::PefCorrection),
+            ("Faktura", _) => FORM_CODE_ATTRIBUTE_RE
+                .captures(xml)
+                .and_then(|c| c.get(1).or_else(|| c.get(2)))
+                .and_then(|m| Self::from_system_code(m.as_str())),
+            _ => None,
+        };
+
+        detected.ok_or_else(|| {
+            KsefError::ApplicationError(
+                0,
+                format!(
+                    "Unsupported invoice schema: root element '{}' in namespace '{}'",
+                    local_name, namespace
+                ),
+            )
+        })
+    }
+
+    pub fn detect_common(invoices: &[&[u8]]) -> Result<Self, KsefError> {
+        let mut detected: Option<InvoiceSchema> = None;
+        for (index, invoice) in invoices.iter().enumerate() {
+            let schema = Self::detect(invoice)?;
+            match detected {
+                None => detected = Some(schema),
+                Some(first) if first != schema => {
+                    return Err(KsefError::ApplicationError(
+                        0,
+                        format!(
+                            "Mixed invoice schemas in one session: {} and {} (invoice #{})",
+                            first, schema, index
+                        ),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        detected.ok_or_else(|| {
+            KsefError::ApplicationError(0, "No invoices to detect the schema from".to_string())
+        })
+    }
+}
+
+impl fmt::Display for InvoiceSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.system_code())
+    }
+}
+
+impl From<InvoiceSchema> for FormCode {
+    fn from(schema: InvoiceSchema) -> Self {
+        schema.form_code()
+    }
+}
+
+fn root_namespace(attributes: &str, prefix: Option<&str>) -> Option<String> {
+    let wanted = match prefix {
+        Some(prefix) => format!("xmlns:{}", prefix),
+        None => "xmlns".to_string(),
+    };
+
+    ATTRIBUTE_RE
+        .captures_iter(attributes)
+        .find(|c| c[1] == wanted)
+        .and_then(|c| c.get(2).or_else(|| c.get(3)))
+        .map(|m| m.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_invoice_schema() {
+        let fa2 = br#"<?xml version="1.0" encoding="UTF-8"?>
+<Faktura xmlns="http://crd.gov.pl/wzor/2023/06/29/12648/"><Naglowek/></Faktura>"#;
+        assert_eq!(InvoiceSchema::detect(fa2).unwrap(), InvoiceSchema::Fa2);
+
+        let fa3 =
+            br#"<tns:Faktura xmlns:tns="http://crd.gov.pl/wzor/2025/06/25/13775/"></tns:Faktura>"#;
+        assert_eq!(InvoiceSchema::detect(fa3).unwrap(), InvoiceSchema::Fa3);
+
+        let fa_rr = br#"<Faktura xmlns="http://crd.gov.pl/wzor/unknown/"><Naglowek>
+<KodFormularza kodSystemowy="FA_RR (1)" wersjaSchemy="1-0E">RR</KodFormularza></Naglowek></Faktura>"#;
+        assert_eq!(InvoiceSchema::detect(fa_rr).unwrap(), InvoiceSchema::FaRr);
+
+        let pef = br#"<Invoice xmlns="urn:oasis:names:specification:ubl:schema:xsd:Invoice-2"/>"#;
+        assert_eq!(InvoiceSchema::detect(pef).unwrap(), InvoiceSchema::Pef);
+
+        assert!(InvoiceSchema::detect(b"<Order xmlns=\"urn:x\"/>").is_err());
+    }
+
+    #[test]
+    fn test_detect_common_rejects_mixed_schemas() {
+        let fa2: &[u8] = br#"<Faktura xmlns="http://crd.gov.pl/wzor/2023/06/29/12648/"/>"#;
+        let fa3: &[u8] = br#"<Faktura xmlns="http://crd.gov.pl/wzor/2025/06/25/13775/"/>"#;
+
+        assert_eq!(
+            InvoiceSchema::detect_common(&[fa2, fa2]).unwrap(),
+            InvoiceSchema::Fa2
+        );
+        assert!(InvoiceSchema::detect_common(&[fa2, fa3]).is_err());
+
+        let form_code = InvoiceSchema::Fa3.form_code();
+        assert_eq!(form_code.system_code, "FA (3)");
+        assert_eq!(form_code.schema_version, "1-0E");
+        assert_eq!(form_code.value, "FA");
+    }
+}
@@ -0,0 +1,70 @@
+use crate::client::error::KsefError;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+pub(crate) fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    PathBuf::from(tmp_path)
+}
+
+pub(crate) async fn write_atomically(path: &Path, content: &[u8]) -> Result<(), KsefError> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let tmp_path = tmp_path(path);
+    if let Err(e) = write_synced(&tmp_path, content).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(e);
+    }
+    rename_durably(&tmp_path, path).await
+}
+
+pub(crate) async fn rename_durably(tmp_path: &Path, path: &Path) -> Result<(), KsefError> {
+    tokio::fs::rename(tmp_path, path).await?;
+    sync_parent_dir(path).await
+}
+
+async fn write_synced(path: &Path, content: &[u8]) -> Result<(), KsefError> {
+    let mut file = tokio::fs::File::create(path).await?;
+    file.write_all(content).await?;
+    file.sync_all().await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn sync_parent_dir(path: &Path) -> Result<(), KsefError> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    tokio::fs::File::open(parent).await?.sync_all().await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn sync_parent_dir(_path: &Path) -> Result<(), KsefError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_atomically() {
+        let dir = std::env::temp_dir().join(format!("ksef-fs-{}", std::process::id()));
+        let path = dir.join("nested").join("state.json");
+
+        write_atomically(&path, b"first").await.unwrap();
+        write_atomically(&path, b"second").await.unwrap();
+
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"second");
+        assert!(!tokio::fs::try_exists(tmp_path(&path)).await.unwrap());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
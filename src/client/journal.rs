@@ -0,0 +1,508 @@
+use crate::client::KsefClient;
+use crate::client::batch_session::close_batch_session::close_batch_session;
+use crate::client::batch_session::full_flow::{
+    BatchSubmissionResult, build_open_batch_request, plan_batch,
+};
+use crate::client::batch_session::open_batch_session::{
+    OpenBatchSessionRequest, OpenBatchSessionResponse, open_batch_session,
+};
+use crate::client::batch_session::streaming_batch::BatchPartFile;
+use crate::client::batch_session::upload_batch_parts::{
+    BatchUploadOptions, upload_batch_parts_with_options,
+};
+use crate::client::batch_session::zip::{FileMetadata, encrypt_zip_parts};
+use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
+use crate::client::fs::write_atomically;
+use crate::client::models::InvoicePayload;
+use crate::client::online_session::close_online_session::close_online_session;
+use crate::client::online_session::encryption::{
+    EncryptedInvoice, generate_encryption_data, prepare_encrypted_invoice,
+};
+use crate::client::online_session::full_flow::OnlineSubmissionResult;
+use crate::client::online_session::get_invoice_status::GetInvoiceStatusResponse;
+use crate::client::online_session::open_online_session::{
+    OpenOnlineSessionRequest, OpenOnlineSessionRequestBuilder, open_online_session,
+};
+use crate::client::online_session::send_invoice::{SendInvoiceOptions, send_encrypted_invoice};
+use crate::client::preflight::{KsefLimits, check_invoice};
+use crate::client::sessions::get_session_invoices::get_session_invoices;
+use crate::client::sessions::get_session_status::{SessionStatusResponse, get_session_status};
+use crate::client::types::{InvoiceHash, ReferenceNumber};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const JOURNAL_VERSION: u32 = 1;
+
+const STATE_FILE: &str = "journal.json";
+const UPLOAD_STATE_FILE: &str = "upload-state.json";
+const INVOICE_FILE: &str = "invoice.xml.aes";
+const SESSION_INVOICES_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmissionKind {
+    Batch,
+    Online,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalStep {
+    Prepared,
+    Opened,
+    Uploaded,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalInvoice {
+    pub invoice_hash: InvoiceHash,
+    pub invoice_size: u64,
+    pub encrypted_invoice_hash: InvoiceHash,
+    pub encrypted_invoice_size: u64,
+    pub offline_mode: bool,
+    pub hash_of_corrected_invoice: Option<InvoiceHash>,
+    pub reference_number: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalState {
+    pub version: u32,
+    pub kind: SubmissionKind,
+    pub step: JournalStep,
+    pub updated_at: DateTime<Utc>,
+    pub session_reference_number: Option<String>,
+    pub batch_request: Option<OpenBatchSessionRequest>,
+    pub batch_response: Option<OpenBatchSessionResponse>,
+    pub online_request: Option<OpenOnlineSessionRequest>,
+    pub invoice: Option<JournalInvoice>,
+}
+
+impl JournalState {
+    fn new(kind: SubmissionKind) -> Self {
+        Self {
+            version: JOURNAL_VERSION,
+            kind,
+            step: JournalStep::Prepared,
+            updated_at: Utc::now(),
+            session_reference_number: None,
+            batch_request: None,
+            batch_response: None,
+            online_request: None,
+            invoice: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step == JournalStep::Closed
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubmissionJournal {
+    dir: PathBuf,
+}
+
+impl SubmissionJournal {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn exists(&self) -> Result<bool, KsefError> {
+        Ok(tokio::fs::try_exists(self.dir.join(STATE_FILE)).await?)
+    }
+
+    pub async fn load(&self) -> Result<JournalState, KsefError> {
+        let content = tokio::fs::read(self.dir.join(STATE_FILE)).await?;
+        let state: JournalState = serde_json::from_slice(&content)?;
+        if state.version > JOURNAL_VERSION {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Unsupported journal version {} (supported up to {})",
+                    state.version, JOURNAL_VERSION
+                ),
+            ));
+        }
+        Ok(state)
+    }
+
+    pub async fn save(&self, state: &JournalState) -> Result<(), KsefError> {
+        write_atomically(
+            &self.dir.join(STATE_FILE),
+            &serde_json::to_vec_pretty(state)?,
+        )
+        .await
+    }
+
+    pub async fn remove(&self) -> Result<(), KsefError> {
+        if tokio::fs::try_exists(&self.dir).await? {
+            tokio::fs::remove_dir_all(&self.dir).await?;
+        }
+        Ok(())
+    }
+
+    async fn record(&self, state: &mut JournalState, step: JournalStep) -> Result<(), KsefError> {
+        state.step = step;
+        state.updated_at = Utc::now();
+        self.save(state).await
+    }
+
+    async fn ensure_empty(&self) -> Result<(), KsefError> {
+        if self.exists().await? {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Journal {} already contains a submission; resume or remove it first",
+                    self.dir.display()
+                ),
+            ));
+        }
+        tokio::fs::create_dir_all(&self.dir).await?;
+        Ok(())
+    }
+
+    async fn write_file(&self, name: &str, content: &[u8]) -> Result<(), KsefError> {
+        write_atomically(&self.dir.join(name), content).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JournalResumeResult {
+    pub kind: SubmissionKind,
+    pub resumed_from: JournalStep,
+    pub session_reference_number: String,
+    pub invoice_reference_number: Option<String>,
+    pub session_status: SessionStatusResponse,
+}
+
+pub async fn submit_batch_with_journal(
+    client: &KsefClient,
+    invoices: &[InvoicePayload],
+    max_part_size_bytes: Option<usize>,
+    options: &SendInvoiceOptions,
+    journal: &SubmissionJournal,
+) -> Result<BatchSubmissionResult, KsefError> {
+    let (schema, zip_result, raw_parts) = plan_batch(invoices, max_part_size_bytes, options)?;
+    journal.ensure_empty().await?;
+
+    let encryption_data = generate_encryption_data(client).await?;
+    let encrypted_parts = encrypt_zip_parts(
+        &raw_parts,
+        &encryption_data.symmetric_key,
+        &encryption_data.initialization_vector,
+    )?;
+
+    let request = build_open_batch_request(
+        schema,
+        &zip_result.metadata,
+        &encrypted_parts,
+        &encryption_data,
+        options,
+    )?;
+
+    for (part, info) in encrypted_parts.iter().zip(&request.batch_file.part_files) {
+        journal.write_file(&info.file_name, &part.content).await?;
+    }
+
+    let mut state = JournalState::new(SubmissionKind::Batch);
+    state.batch_request = Some(request);
+    journal.save(&state).await?;
+
+    let result = resume_batch(client, journal).await?;
+
+    Ok(BatchSubmissionResult {
+        reference_number: result.session_reference_number,
+        number_of_parts: encrypted_parts.len(),
+        total_size_bytes: zip_result.metadata.size,
+    })
+}
+
+pub async fn resume_batch(
+    client: &KsefClient,
+    journal: &SubmissionJournal,
+) -> Result<JournalResumeResult, KsefError> {
+    let mut state = journal.load().await?;
+    if state.kind != SubmissionKind::Batch {
+        return Err(KsefError::ApplicationError(
+            0,
+            "Journal does not describe a batch submission".to_string(),
+        ));
+    }
+    let resumed_from = state.step;
+
+    let request = state.batch_request.clone().ok_or_else(|| {
+        KsefError::InvalidResponse("Batch journal has no session request".to_string())
+    })?;
+
+    if state.step == JournalStep::Prepared {
+        let response = open_batch_session(client, request.clone()).await?;
+        state.session_reference_number = Some(response.reference_number.clone());
+        state.batch_response = Some(response);
+        journal.record(&mut state, JournalStep::Opened).await?;
+    }
+
+    let reference_number = state.session_reference_number.clone().ok_or_else(|| {
+        KsefError::InvalidResponse("Batch journal has no session reference number".to_string())
+    })?;
+
+    if state.step == JournalStep::Opened {
+        let response = state.batch_response.as_ref().ok_or_else(|| {
+            KsefError::InvalidResponse("Batch journal has no upload requests".to_string())
+        })?;
+
+        let mut parts = Vec::with_capacity(request.batch_file.part_files.len());
+        for info in &request.batch_file.part_files {
+            parts.push(BatchPartFile {
+                ordinal_number: info.ordinal_number,
+                path: journal.dir.join(&info.file_name),
+                metadata: FileMetadata {
+                    hash: BASE64.decode(&info.file_hash).map_err(|e| {
+                        KsefError::InvalidResponse(format!("Invalid part hash in journal: {}", e))
+                    })?,
+                    size: info.file_size,
+                },
+            });
+        }
+
+        let options =
+            BatchUploadOptions::default().with_state_file(journal.dir.join(UPLOAD_STATE_FILE));
+        upload_batch_parts_with_options(client, response, &parts, &options)
+            .await?
+            .into_result()?;
+        journal.record(&mut state, JournalStep::Uploaded).await?;
+    }
+
+    if state.step == JournalStep::Uploaded {
+        close_batch_session(client, &reference_number).await?;
+        journal.record(&mut state, JournalStep::Closed).await?;
+    }
+
+    let session_status = get_session_status(client, &reference_number).await?;
+
+    Ok(JournalResumeResult {
+        kind: SubmissionKind::Batch,
+        resumed_from,
+        session_reference_number: reference_number,
+        invoice_reference_number: None,
+        session_status,
+    })
+}
+
+pub async fn submit_online_with_journal(
+    client: &KsefClient,
+    invoice: &[u8],
+    options: &SendInvoiceOptions,
+    journal: &SubmissionJournal,
+) -> Result<OnlineSubmissionResult, KsefError> {
+    options.validate()?;
+    if !options.skip_preflight {
+        check_invoice(invoice, &KsefLimits::default()).into_result()?;
+    }
+    let schema = InvoiceSchema::detect(invoice)?;
+    journal.ensure_empty().await?;
+
+    let encryption_data = generate_encryption_data(client).await?;
+    let encrypted_invoice = prepare_encrypted_invoice(invoice, &encryption_data)?;
+
+    let request = OpenOnlineSessionRequestBuilder::new()
+        .with_schema(schema)
+        .with_encryption_info(encryption_data.to_encryption_info())
+        .build()?;
+
+    journal
+        .write_file(INVOICE_FILE, &encrypted_invoice.encrypted_invoice_content)
+        .await?;
+
+    let mut state = JournalState::new(SubmissionKind::Online);
+    state.online_request = Some(request);
+    state.invoice = Some(JournalInvoice {
+        invoice_hash: encrypted_invoice.invoice_hash,
+        invoice_size: encrypted_invoice.invoice_size,
+        encrypted_invoice_hash: encrypted_invoice.encrypted_invoice_hash,
+        encrypted_invoice_size: encrypted_invoice.encrypted_invoice_size,
+        offline_mode: options.offline_mode,
+        hash_of_corrected_invoice: options.hash_of_corrected_invoice.clone(),
+        reference_number: None,
+    });
+    journal.save(&state).await?;
+
+    let result = resume_online(client, journal).await?;
+
+    Ok(OnlineSubmissionResult {
+        session_reference_number: ReferenceNumber::new(result.session_reference_number),
+        invoice_reference_number: ReferenceNumber::new(
+            result.invoice_reference_number.unwrap_or_default(),
+        ),
+    })
+}
+
+pub async fn resume_online(
+    client: &KsefClient,
+    journal: &SubmissionJournal,
+) -> Result<JournalResumeResult, KsefError> {
+    let mut state = journal.load().await?;
+    if state.kind != SubmissionKind::Online {
+        return Err(KsefError::ApplicationError(
+            0,
+            "Journal does not describe an online submission".to_string(),
+        ));
+    }
+    let resumed_from = state.step;
+
+    if state.step == JournalStep::Prepared {
+        let request = state.online_request.clone().ok_or_else(|| {
+            KsefError::InvalidResponse("Online journal has no session request".to_string())
+        })?;
+        let response = open_online_session(client, request).await?;
+        state.session_reference_number = Some(response.reference_number);
+        journal.record(&mut state, JournalStep::Opened).await?;
+    }
+
+    let reference_number =
+        ReferenceNumber::new(state.session_reference_number.clone().ok_or_else(|| {
+            KsefError::InvalidResponse("Online journal has no session reference number".to_string())
+        })?);
+
+    if state.step == JournalStep::Opened {
+        let invoice = state.invoice.clone().ok_or_else(|| {
+            KsefError::InvalidResponse("Online journal has no invoice".to_string())
+        })?;
+
+        let encrypted_invoice = EncryptedInvoice {
+            invoice_hash: invoice.invoice_hash.clone(),
+            invoice_size: invoice.invoice_size,
+            encrypted_invoice_hash: invoice.encrypted_invoice_hash,
+            encrypted_invoice_size: invoice.encrypted_invoice_size,
+            encrypted_invoice_content: tokio::fs::read(journal.dir.join(INVOICE_FILE)).await?,
+        };
+        let options = SendInvoiceOptions {
+            offline_mode: invoice.offline_mode,
+            hash_of_corrected_invoice: invoice.hash_of_corrected_invoice,
+            skip_preflight: true,
+        };
+
+        let already_sent = if resumed_from == JournalStep::Opened {
+            find_session_invoice(client, &reference_number, &invoice.invoice_hash).await?
+        } else {
+            None
+        };
+        let invoice_reference_number = match already_sent {
+            Some(sent) => sent.reference_number,
+            None => {
+                send_encrypted_invoice(client, &reference_number, &encrypted_invoice, &options)
+                    .await?
+                    .reference_number
+            }
+        };
+        if let Some(invoice) = state.invoice.as_mut() {
+            invoice.reference_number = Some(invoice_reference_number);
+        }
+        journal.record(&mut state, JournalStep::Uploaded).await?;
+    }
+
+    if state.step == JournalStep::Uploaded {
+        close_online_session(client, &reference_number).await?;
+        journal.record(&mut state, JournalStep::Closed).await?;
+    }
+
+    let session_status = get_session_status(client, &reference_number).await?;
+
+    Ok(JournalResumeResult {
+        kind: SubmissionKind::Online,
+        resumed_from,
+        session_reference_number: reference_number.into_inner(),
+        invoice_reference_number: state.invoice.and_then(|i| i.reference_number),
+        session_status,
+    })
+}
+
+async fn find_session_invoice(
+    client: &KsefClient,
+    reference_number: &ReferenceNumber,
+    invoice_hash: &InvoiceHash,
+) -> Result<Option<GetInvoiceStatusResponse>, KsefError> {
+    let mut continuation_token: Option<String> = None;
+    loop {
+        let page = get_session_invoices(
+            client,
+            reference_number.as_str(),
+            Some(SESSION_INVOICES_PAGE_SIZE),
+            continuation_token.as_deref(),
+        )
+        .await?;
+
+        if let Some(found) = page
+            .invoices
+            .into_iter()
+            .find(|i| i.invoice_hash == invoice_hash.as_str())
+        {
+            return Ok(Some(found));
+        }
+
+        continuation_token = page.continuation_token.filter(|t| !t.is_empty());
+        if continuation_token.is_none() {
+            return Ok(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::models::{Encryption, FormCode};
+
+    #[tokio::test]
+    async fn test_journal_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("ksef-journal-{}", std::process::id()));
+        let journal = SubmissionJournal::new(&dir);
+        journal.remove().await.unwrap();
+        assert!(!journal.exists().await.unwrap());
+
+        let mut state = JournalState::new(SubmissionKind::Online);
+        state.online_request = Some(OpenOnlineSessionRequest {
+            form_code: FormCode::from(InvoiceSchema::Fa2),
+            encryption: Encryption {
+                encrypted_symmetric_key: "a2V5".to_string(),
+                initialization_vector: "aXY=".to_string(),
+            },
+        });
+        state.session_reference_number = Some("20250101-SO-0000000000-0000000000-00".to_string());
+        journal
+            .record(&mut state, JournalStep::Opened)
+            .await
+            .unwrap();
+
+        assert!(journal.exists().await.unwrap());
+        assert!(journal.ensure_empty().await.is_err());
+
+        let loaded = journal.load().await.unwrap();
+        assert_eq!(loaded.kind, SubmissionKind::Online);
+        assert_eq!(loaded.step, JournalStep::Opened);
+        assert!(!loaded.is_finished());
+        assert_eq!(
+            loaded
+                .online_request
+                .unwrap()
+                .encryption
+                .initialization_vector,
+            "aXY="
+        );
+
+        state.version = JOURNAL_VERSION + 1;
+        journal.save(&state).await.unwrap();
+        assert!(journal.load().await.is_err());
+
+        journal.remove().await.unwrap();
+        assert!(!dir.exists());
+    }
+}
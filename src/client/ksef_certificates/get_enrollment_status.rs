@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use crate::client::status_codes::EnrollmentStatusCode;
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -18,7 +19,7 @@ pub struct EnrollmentStatusResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrollmentStatus {
-    pub code: i32,
+    pub code: EnrollmentStatusCode,
     pub description: String,
     pub details: Option<Vec<String>>,
 }
@@ -63,7 +64,7 @@ pub async fn get_enrollment_status(
         let parsed: EnrollmentStatusResponse =
             resp.json().await.map_err(KsefError::RequestError)?;
 
-        if parsed.status.code == 100 {
+        if parsed.status.code.is_in_progress() {
             sleep(interval).await;
             continue;
         }
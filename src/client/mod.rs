@@ -11,20 +11,28 @@ use secrecy::ExposeSecret;
 use secrecy::Secret;
 use std::time::Duration;
 
+pub mod archive;
 pub mod auth;
 pub mod batch_session;
+pub mod dates;
 pub mod error;
 pub mod fetching_invoices;
+pub mod form_codes;
+mod fs;
 pub mod get_public_key_certificates;
+pub mod journal;
 pub mod ksef_certificates;
 pub mod ksef_tokens;
 pub mod models;
 pub mod online_session;
 pub mod peppol;
 pub mod permissions;
+pub mod preflight;
 pub mod qr;
+pub mod retry;
 mod routes;
 pub mod sessions;
+pub mod status_codes;
 pub mod traits;
 pub mod types;
 pub mod upo;
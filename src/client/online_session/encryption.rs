@@ -1,5 +1,8 @@
-use crate::client::traits::*;
 use crate::client::error::KsefError;
+use crate::client::models::Encryption;
+use crate::client::traits::*;
+use crate::client::types::InvoiceHash;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use openssl::encrypt::Encrypter;
 use openssl::hash::MessageDigest;
 use openssl::pkey::PKey;
@@ -18,12 +21,67 @@ pub struct EncryptionData {
     pub symmetric_key: Vec<u8>,
 }
 
+impl EncryptionData {
+    pub fn to_encryption_info(&self) -> Encryption {
+        Encryption {
+            encrypted_symmetric_key: BASE64.encode(&self.encrypted_symmetric_key),
+            initialization_vector: BASE64.encode(&self.initialization_vector),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptedInvoice {
+    pub invoice_hash: InvoiceHash,
+    pub invoice_size: u64,
+    pub encrypted_invoice_hash: InvoiceHash,
+    pub encrypted_invoice_size: u64,
+    pub encrypted_invoice_content: Vec<u8>,
+}
+
+impl EncryptedInvoice {
+    pub fn validate(&self) -> Result<(), KsefError> {
+        if self.encrypted_invoice_size != self.encrypted_invoice_content.len() as u64 {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Encrypted invoice size mismatch: declared {}, actual {}",
+                    self.encrypted_invoice_size,
+                    self.encrypted_invoice_content.len()
+                ),
+            ));
+        }
+
+        let computed_hash = BASE64.encode(hash_invoice(&self.encrypted_invoice_content));
+        if self.encrypted_invoice_hash.as_str() != computed_hash {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "Encrypted invoice hash mismatch: declared {}, computed {}",
+                    self.encrypted_invoice_hash, computed_hash
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 pub async fn generate_encryption_data(client: &KsefClient) -> Result<EncryptionData, KsefError> {
-    let public_key_pem = match get_public_key(client).await {
-        Ok(key) => key,
-        Err(e) => return Err(e),
-    };
+    let public_key_pem = get_public_key(client).await?;
+    generate_encryption_data_with_public_key(&public_key_pem)
+}
+
+pub fn generate_encryption_data_from_certificate(
+    certificate: &str,
+) -> Result<EncryptionData, KsefError> {
+    let public_key_pem = public_key_pem_from_certificate(certificate)?;
+    generate_encryption_data_with_public_key(&public_key_pem)
+}
 
+fn generate_encryption_data_with_public_key(
+    public_key_pem: &str,
+) -> Result<EncryptionData, KsefError> {
     let mut symmetric_key = vec![0u8; 32];
     rand_bytes(&mut symmetric_key).map_err(|e| {
         KsefError::ApplicationError(0, format!("Failed to generate symmetric key: {}", e))
@@ -87,10 +145,18 @@ async fn get_public_key(client: &KsefClient) -> Result<String, KsefError> {
             KsefError::ApplicationError(0, "No suitable public key certificate found".to_string())
         })?;
 
-    let pem = format!(
-        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----",
-        &cert.certificate
-    );
+    public_key_pem_from_certificate(&cert.certificate)
+}
+
+fn public_key_pem_from_certificate(certificate: &str) -> Result<String, KsefError> {
+    let pem = if certificate.contains("-----BEGIN CERTIFICATE-----") {
+        certificate.to_string()
+    } else {
+        format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----",
+            certificate.trim()
+        )
+    };
     let cert = X509::from_pem(pem.as_bytes()).map_err(|e| {
         KsefError::ApplicationError(0, format!("Failed to parse certificate PEM: {}", e))
     })?;
@@ -116,6 +182,91 @@ pub fn encrypt_invoice(content: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>,
         .map_err(|e| KsefError::ApplicationError(0, format!("Failed to encrypt invoice: {}", e)))
 }
 
+pub fn encrypted_size(content_len: usize) -> usize {
+    let block_size = Cipher::aes_256_cbc().block_size();
+    (content_len / block_size + 1) * block_size
+}
+
 pub fn hash_invoice(content: &[u8]) -> Vec<u8> {
     sha256(content).to_vec()
 }
+
+pub fn prepare_encrypted_invoice(
+    invoice_xml: &[u8],
+    encryption_data: &EncryptionData,
+) -> Result<EncryptedInvoice, KsefError> {
+    encrypt_invoice_with_hashes(
+        invoice_xml,
+        &encryption_data.symmetric_key,
+        &encryption_data.initialization_vector,
+    )
+}
+
+pub(crate) fn encrypt_invoice_with_hashes(
+    invoice_xml: &[u8],
+    key: &[u8],
+    iv: &[u8],
+) -> Result<EncryptedInvoice, KsefError> {
+    let encrypted_content = encrypt_invoice(invoice_xml, key, iv)?;
+
+    Ok(EncryptedInvoice {
+        invoice_hash: InvoiceHash::new(BASE64.encode(hash_invoice(invoice_xml))),
+        invoice_size: invoice_xml.len() as u64,
+        encrypted_invoice_hash: InvoiceHash::new(BASE64.encode(hash_invoice(&encrypted_content))),
+        encrypted_invoice_size: encrypted_content.len() as u64,
+        encrypted_invoice_content: encrypted_content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+    use openssl::symm::decrypt;
+
+    #[test]
+    fn test_prepare_encrypted_invoice_offline() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let public_key_pem =
+            String::from_utf8(PKey::from_rsa(rsa).unwrap().public_key_to_pem().unwrap()).unwrap();
+
+        let encryption_data = generate_encryption_data_with_public_key(&public_key_pem).unwrap();
+        let encryption = encryption_data.to_encryption_info();
+        assert_eq!(
+            BASE64.decode(&encryption.initialization_vector).unwrap(),
+            encryption_data.initialization_vector
+        );
+
+        let invoice = b"<Faktura/>";
+        let encrypted = prepare_encrypted_invoice(invoice, &encryption_data).unwrap();
+        assert_eq!(encrypted.invoice_size, invoice.len() as u64);
+        assert!(encrypted.validate().is_ok());
+
+        let decrypted = decrypt(
+            Cipher::aes_256_cbc(),
+            &encryption_data.symmetric_key,
+            Some(&encryption_data.initialization_vector),
+            &encrypted.encrypted_invoice_content,
+        )
+        .unwrap();
+        assert_eq!(decrypted, invoice);
+        assert_eq!(
+            encrypted.encrypted_invoice_size as usize,
+            encrypted_size(invoice.len())
+        );
+        for len in [0, 15, 16, 17, 1024] {
+            let content = vec![b'x'; len];
+            let part = encrypt_invoice(
+                &content,
+                &encryption_data.symmetric_key,
+                &encryption_data.initialization_vector,
+            )
+            .unwrap();
+            assert_eq!(part.len(), encrypted_size(len));
+        }
+
+        let mut tampered = encrypted.clone();
+        tampered.encrypted_invoice_content.push(0);
+        assert!(tampered.validate().is_err());
+    }
+}
@@ -1,11 +1,13 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
 use crate::client::online_session::close_online_session::close_online_session;
 use crate::client::online_session::encryption::generate_encryption_data;
 use crate::client::online_session::open_online_session::{
     OpenOnlineSessionRequestBuilder, open_online_session,
 };
-use crate::client::online_session::send_invoice::send_invoice;
+use crate::client::online_session::send_invoice::{SendInvoiceOptions, send_invoice_with_options};
+use crate::client::preflight::{KsefLimits, check_invoice};
 use crate::client::types::ReferenceNumber;
 
 #[derive(Debug, Clone)]
@@ -18,9 +20,24 @@ pub async fn submit_online(
     client: &KsefClient,
     invoice: &[u8],
 ) -> Result<OnlineSubmissionResult, KsefError> {
+    submit_online_with_options(client, invoice, &SendInvoiceOptions::default()).await
+}
+
+pub async fn submit_online_with_options(
+    client: &KsefClient,
+    invoice: &[u8],
+    options: &SendInvoiceOptions,
+) -> Result<OnlineSubmissionResult, KsefError> {
+    options.validate()?;
+    if !options.skip_preflight {
+        check_invoice(invoice, &KsefLimits::default()).into_result()?;
+    }
+    let schema = InvoiceSchema::detect(invoice)?;
+
     let encryption_data = generate_encryption_data(client).await?;
 
     let request = OpenOnlineSessionRequestBuilder::new()
+        .with_schema(schema)
         .with_encryption(
             &encryption_data.encrypted_symmetric_key,
             &encryption_data.initialization_vector,
@@ -30,8 +47,14 @@ pub async fn submit_online(
     let session_response = open_online_session(client, request).await?;
     let session_reference_number = ReferenceNumber::new(session_response.reference_number);
 
-    let send_result =
-        send_invoice(client, &session_reference_number, invoice, &encryption_data).await?;
+    let send_result = send_invoice_with_options(
+        client,
+        &session_reference_number,
+        invoice,
+        &encryption_data,
+        options,
+    )
+    .await?;
     let invoice_reference_number = ReferenceNumber::new(send_result.reference_number);
 
     close_online_session(client, &session_reference_number).await?;
@@ -1,12 +1,17 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
+use crate::client::fetching_invoices::fetch_invoice_metadata::InvoicingMode;
+use crate::client::online_session::watch_invoice_status::{
+    WatchStatusOptions, watch_invoice_status,
+};
 use crate::client::routes;
-use secrecy::ExposeSecret;
+use crate::client::status_codes::InvoiceStatusCode;
+use crate::client::types::ReferenceNumber;
 use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Duration;
-use tokio::time::sleep;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -24,7 +29,7 @@ pub struct GetInvoiceStatusResponse {
     pub permanent_storage_date: Option<DateTime<Utc>>,
     pub upo_download_url: Option<String>,
     pub upo_download_url_expiration_date: Option<DateTime<Utc>>,
-    pub invoicing_mode: Option<String>,
+    pub invoicing_mode: Option<InvoicingMode>,
     #[serde(rename = "status")]
     pub invoice_status: InvoiceStatus,
 }
@@ -32,7 +37,7 @@ pub struct GetInvoiceStatusResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InvoiceStatus {
-    pub code: i32,
+    pub code: InvoiceStatusCode,
     pub description: String,
     pub details: Option<Vec<String>>,
     pub extensions: Option<HashMap<String, String>>,
@@ -42,6 +47,28 @@ pub async fn get_invoice_status(
     client: &KsefClient,
     session_reference_number: &str,
     invoice_reference_number: &str,
+) -> Result<GetInvoiceStatusResponse, KsefError> {
+    let mut updates = watch_invoice_status(
+        client,
+        ReferenceNumber::new(session_reference_number),
+        ReferenceNumber::new(invoice_reference_number),
+        WatchStatusOptions::default(),
+    );
+
+    let mut last = None;
+    while let Some(update) = updates.next().await {
+        last = Some(update?);
+    }
+
+    last.ok_or_else(|| {
+        KsefError::RuntimeError("Invoice status polling finished without a status".to_string())
+    })
+}
+
+pub async fn get_invoice_status_once(
+    client: &KsefClient,
+    session_reference_number: &str,
+    invoice_reference_number: &str,
 ) -> Result<GetInvoiceStatusResponse, KsefError> {
     let url = client.url_for(&format!(
         "{}/{}/invoices/{}",
@@ -58,40 +85,24 @@ pub async fn get_invoice_status(
         ));
     }
 
-    let timeout = Duration::from_secs(120);
-    let interval = Duration::from_secs(2);
-    let start = std::time::Instant::now();
-
-    loop {
-        if start.elapsed() > timeout {
-            return Err(KsefError::RuntimeError(
-                "Invoice status polling timed out".to_string(),
-            ));
-        }
-
-        let resp = client
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .bearer_auth(access_token)
-            .send()
-            .await
-            .map_err(KsefError::RequestError)?;
-
-        let status = resp.status();
-        if !status.is_success() {
-            let body = resp.text().await.unwrap_or_default();
-            return Err(KsefError::from_api_response(status.as_u16(), body));
-        }
+    let resp = client
+        .client
+        .get(&url)
+        .header("Accept", "application/json")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(KsefError::RequestError)?;
 
-        let parsed: GetInvoiceStatusResponse =
-            resp.json().await.map_err(KsefError::RequestError)?;
-
-        if parsed.invoice_status.code == 100 || parsed.invoice_status.code == 150 {
-            sleep(interval).await;
-            continue;
-        }
-
-        return Ok(parsed);
+    let status = resp.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
     }
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(status.as_u16(), body));
+    }
+
+    let parsed: GetInvoiceStatusResponse = resp.json().await.map_err(KsefError::RequestError)?;
+    Ok(parsed)
 }
@@ -4,3 +4,5 @@ pub mod full_flow;
 pub mod get_invoice_status;
 pub mod open_online_session;
 pub mod send_invoice;
+pub mod send_invoices;
+pub mod watch_invoice_status;
@@ -1,10 +1,14 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
 use crate::client::models::{Encryption, FormCode};
 use crate::client::routes;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+const DEFAULT_SCHEMA: InvoiceSchema = InvoiceSchema::Fa2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenOnlineSessionRequest {
     #[serde(rename = "formCode")]
     pub form_code: FormCode,
@@ -17,7 +21,7 @@ pub struct OpenOnlineSessionResponse {
     #[serde(rename = "referenceNumber")]
     pub reference_number: String,
     #[serde(rename = "validUntil")]
-    pub valid_until: String,
+    pub valid_until: DateTime<Utc>,
 }
 
 pub struct OpenOnlineSessionRequestBuilder {
@@ -26,16 +30,18 @@ pub struct OpenOnlineSessionRequestBuilder {
     value: Option<String>,
     encrypted_symmetric_key: Option<Vec<u8>>,
     initialization_vector: Option<Vec<u8>>,
+    encryption: Option<Encryption>,
 }
 
 impl OpenOnlineSessionRequestBuilder {
     pub fn new() -> Self {
         Self {
-            system_code: Some("FA (2)".to_string()),
-            schema_version: Some("1-0E".to_string()),
-            value: Some("FA".to_string()),
+            system_code: Some(InvoiceSchema::Fa2.system_code().to_string()),
+            schema_version: Some(InvoiceSchema::Fa2.schema_version().to_string()),
+            value: Some(InvoiceSchema::Fa2.value().to_string()),
             encrypted_symmetric_key: None,
             initialization_vector: None,
+            encryption: None,
         }
     }
 
@@ -54,13 +60,46 @@ impl OpenOnlineSessionRequestBuilder {
         self
     }
 
+    pub fn with_form_code(mut self, form_code: FormCode) -> Self {
+        self.system_code = Some(form_code.system_code);
+        self.schema_version = Some(form_code.schema_version);
+        self.value = Some(form_code.value);
+        self
+    }
+
+    pub fn with_schema(self, schema: InvoiceSchema) -> Self {
+        self.with_form_code(schema.form_code())
+    }
+
     pub fn with_encryption(mut self, key: &[u8], iv: &[u8]) -> Self {
         self.encrypted_symmetric_key = Some(key.to_vec());
         self.initialization_vector = Some(iv.to_vec());
         self
     }
 
+    pub fn with_encryption_info(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
     pub fn build(self) -> Result<OpenOnlineSessionRequest, KsefError> {
+        if let Some(encryption) = self.encryption {
+            return Ok(OpenOnlineSessionRequest {
+                form_code: FormCode {
+                    system_code: self
+                        .system_code
+                        .unwrap_or_else(|| DEFAULT_SCHEMA.system_code().to_string()),
+                    schema_version: self
+                        .schema_version
+                        .unwrap_or_else(|| DEFAULT_SCHEMA.schema_version().to_string()),
+                    value: self
+                        .value
+                        .unwrap_or_else(|| DEFAULT_SCHEMA.value().to_string()),
+                },
+                encryption,
+            });
+        }
+
         let key = self
             .encrypted_symmetric_key
             .ok_or(KsefError::ApplicationError(
@@ -75,9 +114,13 @@ impl OpenOnlineSessionRequestBuilder {
             ))?;
 
         Ok(OpenOnlineSessionRequest::new(
-            self.system_code.as_deref().unwrap_or("FA"),
-            self.schema_version.as_deref().unwrap_or("1-0E"),
-            self.value.as_deref().unwrap_or("FA"),
+            self.system_code
+                .as_deref()
+                .unwrap_or(DEFAULT_SCHEMA.system_code()),
+            self.schema_version
+                .as_deref()
+                .unwrap_or(DEFAULT_SCHEMA.schema_version()),
+            self.value.as_deref().unwrap_or(DEFAULT_SCHEMA.value()),
             &key,
             &iv,
         ))
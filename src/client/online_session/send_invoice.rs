@@ -1,12 +1,14 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
-use crate::client::online_session::encryption::{EncryptionData, encrypt_invoice, hash_invoice};
+use crate::client::online_session::encryption::{
+    EncryptedInvoice, EncryptionData, encrypt_invoice_with_hashes,
+};
 use crate::client::routes;
-use crate::client::types::ReferenceNumber;
+use crate::client::types::{InvoiceHash, ReferenceNumber};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SendInvoiceRequest {
     #[serde(rename = "invoiceHash")]
     pub invoice_hash: String,
@@ -24,6 +26,65 @@ pub struct SendInvoiceRequest {
     pub hash_of_corrected_invoice: Option<String>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct SendInvoiceOptions {
+    pub offline_mode: bool,
+    pub hash_of_corrected_invoice: Option<InvoiceHash>,
+    pub(crate) skip_preflight: bool,
+}
+
+impl SendInvoiceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn offline() -> Self {
+        Self {
+            offline_mode: true,
+            hash_of_corrected_invoice: None,
+            skip_preflight: false,
+        }
+    }
+
+    pub fn technical_correction(hash_of_corrected_invoice: impl Into<InvoiceHash>) -> Self {
+        Self {
+            offline_mode: true,
+            hash_of_corrected_invoice: Some(hash_of_corrected_invoice.into()),
+            skip_preflight: false,
+        }
+    }
+
+    pub fn with_offline_mode(mut self, offline_mode: bool) -> Self {
+        self.offline_mode = offline_mode;
+        self
+    }
+
+    pub fn with_hash_of_corrected_invoice(mut self, hash: impl Into<InvoiceHash>) -> Self {
+        self.hash_of_corrected_invoice = Some(hash.into());
+        self
+    }
+
+    pub fn with_skip_preflight(mut self, skip_preflight: bool) -> Self {
+        self.skip_preflight = skip_preflight;
+        self
+    }
+
+    pub fn skip_preflight(&self) -> bool {
+        self.skip_preflight
+    }
+
+    pub fn validate(&self) -> Result<(), KsefError> {
+        if self.hash_of_corrected_invoice.is_some() && !self.offline_mode {
+            return Err(KsefError::ApplicationError(
+                0,
+                "hash_of_corrected_invoice is only allowed for invoices sent in offline mode"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SendInvoiceResponse {
     #[serde(rename = "referenceNumber")]
@@ -36,27 +97,85 @@ pub async fn send_invoice(
     invoice_xml: &[u8],
     encryption_data: &EncryptionData,
 ) -> Result<SendInvoiceResponse, KsefError> {
-    let invoice_hash = hash_invoice(invoice_xml);
-    let invoice_size = invoice_xml.len() as u64;
+    send_invoice_with_options(
+        client,
+        session_reference_number,
+        invoice_xml,
+        encryption_data,
+        &SendInvoiceOptions::default(),
+    )
+    .await
+}
 
-    let encrypted_content = encrypt_invoice(
+pub async fn send_invoice_with_options(
+    client: &KsefClient,
+    session_reference_number: &ReferenceNumber,
+    invoice_xml: &[u8],
+    encryption_data: &EncryptionData,
+    options: &SendInvoiceOptions,
+) -> Result<SendInvoiceResponse, KsefError> {
+    let request = build_send_invoice_request(
         invoice_xml,
         &encryption_data.symmetric_key,
         &encryption_data.initialization_vector,
+        options,
     )?;
 
-    let encrypted_invoice_hash = hash_invoice(&encrypted_content);
-    let encrypted_invoice_size = encrypted_content.len() as u64;
-
-    let request = SendInvoiceRequest {
-        invoice_hash: BASE64.encode(invoice_hash),
-        invoice_size,
-        encrypted_invoice_hash: BASE64.encode(encrypted_invoice_hash),
-        encrypted_invoice_size,
-        encrypted_invoice_content: BASE64.encode(encrypted_content),
-        offline_mode: false,
-        hash_of_corrected_invoice: None,
-    };
+    post_send_invoice_request(client, session_reference_number, &request).await
+}
+
+pub async fn send_encrypted_invoice(
+    client: &KsefClient,
+    session_reference_number: &ReferenceNumber,
+    encrypted_invoice: &EncryptedInvoice,
+    options: &SendInvoiceOptions,
+) -> Result<SendInvoiceResponse, KsefError> {
+    encrypted_invoice.validate()?;
+    let request = SendInvoiceRequest::from_encrypted_invoice(encrypted_invoice, options)?;
+
+    post_send_invoice_request(client, session_reference_number, &request).await
+}
+
+pub fn build_send_invoice_request(
+    invoice_xml: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    options: &SendInvoiceOptions,
+) -> Result<SendInvoiceRequest, KsefError> {
+    options.validate()?;
+
+    let encrypted_invoice = encrypt_invoice_with_hashes(invoice_xml, key, iv)?;
+
+    SendInvoiceRequest::from_encrypted_invoice(&encrypted_invoice, options)
+}
+
+impl SendInvoiceRequest {
+    pub fn from_encrypted_invoice(
+        encrypted_invoice: &EncryptedInvoice,
+        options: &SendInvoiceOptions,
+    ) -> Result<Self, KsefError> {
+        options.validate()?;
+
+        Ok(SendInvoiceRequest {
+            invoice_hash: encrypted_invoice.invoice_hash.to_string(),
+            invoice_size: encrypted_invoice.invoice_size,
+            encrypted_invoice_hash: encrypted_invoice.encrypted_invoice_hash.to_string(),
+            encrypted_invoice_size: encrypted_invoice.encrypted_invoice_size,
+            encrypted_invoice_content: BASE64.encode(&encrypted_invoice.encrypted_invoice_content),
+            offline_mode: options.offline_mode,
+            hash_of_corrected_invoice: options
+                .hash_of_corrected_invoice
+                .as_ref()
+                .map(|hash| hash.to_string()),
+        })
+    }
+}
+
+pub async fn post_send_invoice_request(
+    client: &KsefClient,
+    session_reference_number: &ReferenceNumber,
+    request: &SendInvoiceRequest,
+) -> Result<SendInvoiceResponse, KsefError> {
     let url = client.url_for(&format!(
         "{}/{}/invoices",
         routes::SESSIONS_ONLINE_PATH,
@@ -76,12 +195,16 @@ pub async fn send_invoice(
         .post(&url)
         .header("Accept", "application/json")
         .bearer_auth(token)
-        .json(&request)
+        .json(request)
         .send()
         .await?;
 
     let status = resp.status();
 
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(KsefError::rate_limited(resp.headers()));
+    }
+
     if !status.is_success() {
         let code = status.as_u16();
         let body = resp.text().await.unwrap_or_default();
@@ -92,3 +215,25 @@ pub async fn send_invoice(
 
     Ok(parsed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_validation() {
+        assert!(SendInvoiceOptions::default().validate().is_ok());
+        assert!(SendInvoiceOptions::offline().validate().is_ok());
+        assert!(
+            SendInvoiceOptions::technical_correction("hash")
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            SendInvoiceOptions::new()
+                .with_hash_of_corrected_invoice("hash")
+                .validate()
+                .is_err()
+        );
+    }
+}
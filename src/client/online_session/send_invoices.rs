@@ -0,0 +1,119 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::online_session::encryption::EncryptionData;
+use crate::client::online_session::send_invoice::{
+    SendInvoiceOptions, build_send_invoice_request, post_send_invoice_request,
+};
+use crate::client::retry::RetryPolicy;
+use crate::client::types::{InvoiceHash, ReferenceNumber};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct SendInvoicesOptions {
+    pub concurrency: usize,
+    pub retry_policy: RetryPolicy,
+    pub send_options: SendInvoiceOptions,
+}
+
+impl Default for SendInvoicesOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+            send_options: SendInvoiceOptions::default(),
+        }
+    }
+}
+
+impl SendInvoicesOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_send_options(mut self, send_options: SendInvoiceOptions) -> Self {
+        self.send_options = send_options;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct SentInvoice {
+    pub index: usize,
+    pub invoice_hash: Option<InvoiceHash>,
+    pub result: Result<ReferenceNumber, KsefError>,
+}
+
+pub fn send_invoices<'a, S>(
+    client: &'a KsefClient,
+    session_reference_number: ReferenceNumber,
+    invoices: S,
+    encryption_data: &EncryptionData,
+    options: SendInvoicesOptions,
+) -> BoxStream<'a, SentInvoice>
+where
+    S: Stream<Item = Vec<u8>> + Send + 'a,
+{
+    let key: Arc<[u8]> = encryption_data.symmetric_key.as_slice().into();
+    let iv: Arc<[u8]> = encryption_data.initialization_vector.as_slice().into();
+    let concurrency = options.concurrency.max(1);
+    let retry_policy = options.retry_policy;
+    let send_options = options.send_options;
+
+    invoices
+        .enumerate()
+        .map(move |(index, invoice)| {
+            let key = key.clone();
+            let iv = iv.clone();
+            let send_options = send_options.clone();
+            let retry_policy = retry_policy.clone();
+            let session_reference_number = session_reference_number.clone();
+
+            async move {
+                let prepared = tokio::task::spawn_blocking(move || {
+                    build_send_invoice_request(&invoice, &key, &iv, &send_options)
+                })
+                .await
+                .map_err(|e| {
+                    KsefError::RuntimeError(format!("Invoice encryption task failed: {}", e))
+                })
+                .and_then(|request| request);
+
+                let request = match prepared {
+                    Ok(request) => request,
+                    Err(e) => {
+                        return SentInvoice {
+                            index,
+                            invoice_hash: None,
+                            result: Err(e),
+                        };
+                    }
+                };
+
+                let result = retry_policy
+                    .run_if(KsefError::is_safe_to_resend, || {
+                        post_send_invoice_request(client, &session_reference_number, &request)
+                    })
+                    .await
+                    .map(|response| ReferenceNumber::new(response.reference_number));
+
+                SentInvoice {
+                    index,
+                    invoice_hash: Some(InvoiceHash::new(request.invoice_hash.clone())),
+                    result,
+                }
+            }
+        })
+        .buffered(concurrency)
+        .boxed()
+}
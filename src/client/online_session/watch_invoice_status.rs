@@ -0,0 +1,235 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::online_session::get_invoice_status::{
+    GetInvoiceStatusResponse, get_invoice_status_once,
+};
+use crate::client::status_codes::InvoiceStatusCode;
+use crate::client::types::ReferenceNumber;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+#[derive(Debug, Clone)]
+pub struct WatchStatusOptions {
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub concurrency: usize,
+}
+
+impl Default for WatchStatusOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            timeout: Duration::from_secs(120),
+            concurrency: 8,
+        }
+    }
+}
+
+impl WatchStatusOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct InvoiceStatusUpdate {
+    pub invoice_reference_number: ReferenceNumber,
+    pub result: Result<GetInvoiceStatusResponse, KsefError>,
+}
+
+struct WatchState {
+    last_code: Option<InvoiceStatusCode>,
+    started: Instant,
+    polled: bool,
+    done: bool,
+}
+
+struct BulkWatchState {
+    pending: Vec<(ReferenceNumber, Option<InvoiceStatusCode>)>,
+    started: Instant,
+    polled: bool,
+}
+
+fn polling_timed_out() -> KsefError {
+    KsefError::RuntimeError("Invoice status polling timed out".to_string())
+}
+
+async fn wait_before_poll(polled: bool, interval: Duration) {
+    if polled {
+        sleep(interval).await;
+    }
+}
+
+pub fn watch_invoice_status<'a>(
+    client: &'a KsefClient,
+    session_reference_number: ReferenceNumber,
+    invoice_reference_number: ReferenceNumber,
+    options: WatchStatusOptions,
+) -> BoxStream<'a, Result<GetInvoiceStatusResponse, KsefError>> {
+    let state = WatchState {
+        last_code: None,
+        started: Instant::now(),
+        polled: false,
+        done: false,
+    };
+
+    stream::unfold(state, move |mut state| {
+        let session_reference_number = session_reference_number.clone();
+        let invoice_reference_number = invoice_reference_number.clone();
+        let options = options.clone();
+
+        async move {
+            if state.done {
+                return None;
+            }
+
+            loop {
+                wait_before_poll(state.polled, options.interval).await;
+                state.polled = true;
+
+                if state.started.elapsed() > options.timeout {
+                    state.done = true;
+                    return Some((Err(polling_timed_out()), state));
+                }
+
+                match get_invoice_status_once(
+                    client,
+                    &session_reference_number,
+                    &invoice_reference_number,
+                )
+                .await
+                {
+                    Ok(status) => {
+                        let code = status.invoice_status.code;
+                        if code.is_final() {
+                            state.done = true;
+                            return Some((Ok(status), state));
+                        }
+                        if state.last_code != Some(code) {
+                            state.last_code = Some(code);
+                            return Some((Ok(status), state));
+                        }
+                    }
+                    Err(e) if e.is_transient() => {
+                        if let KsefError::RateLimited(Some(retry_after)) = e {
+                            sleep(retry_after).await;
+                        }
+                    }
+                    Err(e) => {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+pub fn watch_invoice_statuses<'a>(
+    client: &'a KsefClient,
+    session_reference_number: ReferenceNumber,
+    invoice_reference_numbers: Vec<ReferenceNumber>,
+    options: WatchStatusOptions,
+) -> BoxStream<'a, InvoiceStatusUpdate> {
+    let state = BulkWatchState {
+        pending: invoice_reference_numbers
+            .into_iter()
+            .map(|reference_number| (reference_number, None))
+            .collect(),
+        started: Instant::now(),
+        polled: false,
+    };
+
+    stream::unfold(state, move |mut state| {
+        let session_reference_number = session_reference_number.clone();
+        let options = options.clone();
+
+        async move {
+            if state.pending.is_empty() {
+                return None;
+            }
+
+            wait_before_poll(state.polled, options.interval).await;
+            state.polled = true;
+
+            if state.started.elapsed() > options.timeout {
+                let updates = state
+                    .pending
+                    .drain(..)
+                    .map(|(invoice_reference_number, _)| InvoiceStatusUpdate {
+                        invoice_reference_number,
+                        result: Err(polling_timed_out()),
+                    })
+                    .collect::<Vec<_>>();
+                return Some((updates, state));
+            }
+
+            let pending = std::mem::take(&mut state.pending);
+            let results = stream::iter(pending)
+                .map(|(invoice_reference_number, last_code)| {
+                    let session_reference_number = &session_reference_number;
+                    async move {
+                        let result = get_invoice_status_once(
+                            client,
+                            session_reference_number,
+                            &invoice_reference_number,
+                        )
+                        .await;
+                        (invoice_reference_number, last_code, result)
+                    }
+                })
+                .buffered(options.concurrency.max(1))
+                .collect::<Vec<_>>()
+                .await;
+
+            let mut updates = Vec::new();
+            for (invoice_reference_number, last_code, result) in results {
+                match result {
+                    Ok(status) => {
+                        let code = status.invoice_status.code;
+                        if code.is_in_progress() {
+                            state
+                                .pending
+                                .push((invoice_reference_number.clone(), Some(code)));
+                            if last_code == Some(code) {
+                                continue;
+                            }
+                        }
+                        updates.push(InvoiceStatusUpdate {
+                            invoice_reference_number,
+                            result: Ok(status),
+                        });
+                    }
+                    Err(e) if e.is_transient() => {
+                        state.pending.push((invoice_reference_number, last_code));
+                    }
+                    Err(e) => updates.push(InvoiceStatusUpdate {
+                        invoice_reference_number,
+                        result: Err(e),
+                    }),
+                }
+            }
+
+            Some((updates, state))
+        }
+    })
+    .flat_map(stream::iter)
+    .boxed()
+}
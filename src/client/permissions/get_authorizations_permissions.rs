@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,7 +79,7 @@ pub struct AuthorizationGrant {
     pub authorization_scope: String,
     pub description: String,
     pub subject_entity_details: Option<SubjectEntityDetails>,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
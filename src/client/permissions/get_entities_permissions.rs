@@ -2,6 +2,7 @@ use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,7 +39,7 @@ pub struct EntityPermissionItem {
     pub context_identifier: EntitiesContextIdentifier,
     pub permission_scope: String,
     pub description: String,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
     pub can_delegate: bool,
 }
 
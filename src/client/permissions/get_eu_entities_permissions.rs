@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::{DateTime, NaiveDate, Utc};
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -50,7 +51,7 @@ pub struct SubjectPersonDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identifier: Option<PersonIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub birth_date: Option<String>,
+    pub birth_date: Option<NaiveDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id_document: Option<IdDocument>,
 }
@@ -91,7 +92,7 @@ pub struct EuEntityPermission {
     pub subject_entity_details: Option<SubjectEntityDetails>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub eu_entity_details: Option<EuEntityDetails>,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,8 +1,9 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
+use crate::client::routes;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use crate::client::routes;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -60,7 +61,7 @@ pub struct SubjectPersonDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub person_identifier: Option<PersonIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub birth_date: Option<String>,
+    pub birth_date: Option<NaiveDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id_document: Option<IdDocument>,
 }
@@ -95,7 +96,7 @@ pub struct PersonalPermission {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id_document: Option<IdDocument>,
     pub permission_state: String,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
     pub can_delegate: bool,
 }
 
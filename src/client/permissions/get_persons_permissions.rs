@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -182,7 +183,7 @@ pub struct SubjectPersonDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub person_identifier: Option<PersonIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub birth_date: Option<String>,
+    pub birth_date: Option<NaiveDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id_document: Option<IdDocument>,
 }
@@ -213,7 +214,7 @@ pub struct PersonPermission {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subject_entity_details: Option<SubjectEntityDetails>,
     pub permission_state: String,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
     pub can_delegate: bool,
 }
 
@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::{DateTime, NaiveDate, Utc};
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 
@@ -57,7 +58,7 @@ pub struct SubjectPersonDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub person_identifier: Option<PersonIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub birth_date: Option<String>,
+    pub birth_date: Option<NaiveDate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id_document: Option<IdDocument>,
 }
@@ -87,7 +88,7 @@ pub struct SubunitPermission {
     pub id_document: Option<IdDocument>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subunit_name: Option<String>,
-    pub start_date: String,
+    pub start_date: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub can_delegate: Option<bool>,
 }
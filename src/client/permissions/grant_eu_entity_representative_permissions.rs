@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,7 +150,7 @@ pub enum EuEntityRepresentativePersonIdentifierType {
 pub struct EuEntityRepresentativePersonByFpNoId {
     pub first_name: String,
     pub last_name: String,
-    pub birth_date: String,
+    pub birth_date: NaiveDate,
     pub id_document: EuEntityRepresentativeIdDocument,
 }
 
@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::routes;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -325,7 +326,7 @@ pub enum IndirectPersonIdentifierType {
 pub struct IndirectPersonByFpNoId {
     pub first_name: String,
     pub last_name: String,
-    pub birth_date: String,
+    pub birth_date: NaiveDate,
     pub id_document: IndirectIdDocument,
 }
 
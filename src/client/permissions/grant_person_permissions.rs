@@ -2,6 +2,7 @@ use crate::client::KsefClient;
 use crate::client::error::KsefError;
 use crate::client::permissions::get_operation_status::process_status_response;
 use crate::client::routes;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -305,7 +306,7 @@ pub enum PersonIdentifierType {
 pub struct PersonByFpNoId {
     pub first_name: String,
     pub last_name: String,
-    pub birth_date: String,
+    pub birth_date: NaiveDate,
     pub id_document: IdDocument,
 }
 
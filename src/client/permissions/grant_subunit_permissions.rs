@@ -4,6 +4,7 @@ use crate::client::permissions::get_operation_status::{
     OperationStatusResponse, get_operation_status,
 };
 use crate::client::routes;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -181,7 +182,7 @@ pub enum SubunitPersonIdentifierType {
 pub struct SubunitPersonByFpNoId {
     pub first_name: String,
     pub last_name: String,
-    pub birth_date: String,
+    pub birth_date: NaiveDate,
     pub id_document: SubunitIdDocument,
 }
 
@@ -0,0 +1,581 @@
+use crate::client::error::KsefError;
+use crate::client::form_codes::InvoiceSchema;
+use crate::client::models::InvoicePayload;
+use std::collections::HashSet;
+use std::fmt;
+
+const MIB: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct KsefLimits {
+    pub max_invoice_size: usize,
+    pub max_invoice_size_with_attachments: usize,
+    pub max_batch_invoices: usize,
+    pub max_part_size: usize,
+    pub max_parts: usize,
+    pub max_batch_size: usize,
+}
+
+impl Default for KsefLimits {
+    fn default() -> Self {
+        Self {
+            max_invoice_size: MIB,
+            max_invoice_size_with_attachments: 3 * MIB,
+            max_batch_invoices: 10_000,
+            max_part_size: 100 * MIB,
+            max_parts: 50,
+            max_batch_size: 5 * 1024 * MIB,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreflightSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreflightIssueKind {
+    EmptyInvoice,
+    InvoiceTooLarge,
+    InvalidUtf8,
+    MalformedXml,
+    UnsupportedSchema,
+    MixedSchemas,
+    EmptyFilename,
+    InvalidFilename,
+    DuplicateFilename,
+    MissingXmlExtension,
+    NoInvoices,
+    TooManyInvoices,
+    PartTooLarge,
+    TooManyParts,
+    BatchTooLarge,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreflightIssue {
+    pub severity: PreflightSeverity,
+    pub kind: PreflightIssueKind,
+    pub index: Option<usize>,
+    pub filename: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for PreflightIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.filename, self.index) {
+            (Some(filename), _) => write!(f, "{}: {}", filename, self.message),
+            (None, Some(index)) => write!(f, "invoice #{}: {}", index, self.message),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &PreflightIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == PreflightSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == PreflightSeverity::Warning)
+    }
+
+    pub fn has(&self, kind: PreflightIssueKind) -> bool {
+        self.issues.iter().any(|i| i.kind == kind)
+    }
+
+    pub fn into_result(self) -> Result<Self, KsefError> {
+        if self.is_ok() {
+            Ok(self)
+        } else {
+            Err(KsefError::PreflightError(Box::new(self)))
+        }
+    }
+
+    fn error(
+        &mut self,
+        kind: PreflightIssueKind,
+        index: Option<usize>,
+        filename: Option<&str>,
+        message: String,
+    ) {
+        self.push(PreflightSeverity::Error, kind, index, filename, message);
+    }
+
+    fn warning(
+        &mut self,
+        kind: PreflightIssueKind,
+        index: Option<usize>,
+        filename: Option<&str>,
+        message: String,
+    ) {
+        self.push(PreflightSeverity::Warning, kind, index, filename, message);
+    }
+
+    fn push(
+        &mut self,
+        severity: PreflightSeverity,
+        kind: PreflightIssueKind,
+        index: Option<usize>,
+        filename: Option<&str>,
+        message: String,
+    ) {
+        self.issues.push(PreflightIssue {
+            severity,
+            kind,
+            index,
+            filename: filename.map(|f| f.to_string()),
+            message,
+        });
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<_> = self.errors().collect();
+        write!(f, "{} error(s)", errors.len())?;
+        if let Some(first) = errors.first() {
+            write!(f, ", first: {}", first)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn check_invoice(invoice_xml: &[u8], limits: &KsefLimits) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    check_invoice_content(&mut report, invoice_xml, None, None, limits);
+    report
+}
+
+pub fn check_batch(invoices: &[InvoicePayload], limits: &KsefLimits) -> PreflightReport {
+    let mut preflight = BatchPreflight::new(limits.clone());
+    for invoice in invoices {
+        preflight.add(invoice);
+    }
+    preflight.finish()
+}
+
+pub fn check_batch_plan(
+    zip_size: usize,
+    part_sizes: &[usize],
+    limits: &KsefLimits,
+) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    check_plan(&mut report, zip_size, part_sizes, limits);
+    report
+}
+
+#[derive(Debug)]
+pub struct BatchPreflight {
+    limits: KsefLimits,
+    filenames: HashSet<String>,
+    schema: Option<InvoiceSchema>,
+    count: usize,
+    report: PreflightReport,
+}
+
+impl BatchPreflight {
+    pub fn new(limits: KsefLimits) -> Self {
+        Self {
+            limits,
+            filenames: HashSet::new(),
+            schema: None,
+            count: 0,
+            report: PreflightReport::default(),
+        }
+    }
+
+    pub fn add(&mut self, invoice: &InvoicePayload) {
+        let index = self.count;
+        self.count += 1;
+
+        let filename = invoice.filename.as_str();
+        check_filename(&mut self.report, index, filename);
+        if !filename.is_empty() && !self.filenames.insert(filename.to_string()) {
+            self.report.error(
+                PreflightIssueKind::DuplicateFilename,
+                Some(index),
+                Some(filename),
+                "duplicate file name in batch".to_string(),
+            );
+        }
+
+        let schema = check_invoice_content(
+            &mut self.report,
+            &invoice.content,
+            Some(index),
+            Some(filename),
+            &self.limits,
+        );
+
+        match (self.schema, schema) {
+            (None, Some(schema)) => self.schema = Some(schema),
+            (Some(first), Some(schema)) if first != schema => self.report.error(
+                PreflightIssueKind::MixedSchemas,
+                Some(index),
+                Some(filename),
+                format!("schema {} differs from {} used by the batch", schema, first),
+            ),
+            _ => {}
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn report(&self) -> &PreflightReport {
+        &self.report
+    }
+
+    pub fn finish(mut self) -> PreflightReport {
+        if self.count == 0 {
+            self.report.error(
+                PreflightIssueKind::NoInvoices,
+                None,
+                None,
+                "batch contains no invoices".to_string(),
+            );
+        }
+        if self.count > self.limits.max_batch_invoices {
+            self.report.error(
+                PreflightIssueKind::TooManyInvoices,
+                None,
+                None,
+                format!(
+                    "batch contains {} invoices, limit is {}",
+                    self.count, self.limits.max_batch_invoices
+                ),
+            );
+        }
+        self.report
+    }
+}
+
+fn check_plan(
+    report: &mut PreflightReport,
+    zip_size: usize,
+    part_sizes: &[usize],
+    limits: &KsefLimits,
+) {
+    if zip_size > limits.max_batch_size {
+        report.error(
+            PreflightIssueKind::BatchTooLarge,
+            None,
+            None,
+            format!(
+                "batch ZIP has {} bytes, limit is {}",
+                zip_size, limits.max_batch_size
+            ),
+        );
+    }
+    if part_sizes.len() > limits.max_parts {
+        report.error(
+            PreflightIssueKind::TooManyParts,
+            None,
+            None,
+            format!(
+                "batch is split into {} parts, limit is {}",
+                part_sizes.len(),
+                limits.max_parts
+            ),
+        );
+    }
+    for (i, size) in part_sizes.iter().enumerate() {
+        if *size > limits.max_part_size {
+            report.error(
+                PreflightIssueKind::PartTooLarge,
+                None,
+                None,
+                format!(
+                    "part {} has {} bytes, limit is {}",
+                    i + 1,
+                    size,
+                    limits.max_part_size
+                ),
+            );
+        }
+    }
+}
+
+fn check_filename(report: &mut PreflightReport, index: usize, filename: &str) {
+    if filename.trim().is_empty() {
+        report.error(
+            PreflightIssueKind::EmptyFilename,
+            Some(index),
+            None,
+            "file name is empty".to_string(),
+        );
+        return;
+    }
+
+    let has_invalid_chars = filename.chars().any(|c| {
+        c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+    });
+    if has_invalid_chars || filename == "." || filename == ".." {
+        report.error(
+            PreflightIssueKind::InvalidFilename,
+            Some(index),
+            Some(filename),
+            "file name contains path separators or invalid characters".to_string(),
+        );
+    }
+
+    if !filename.to_ascii_lowercase().ends_with(".xml") {
+        report.warning(
+            PreflightIssueKind::MissingXmlExtension,
+            Some(index),
+            Some(filename),
+            "file name has no .xml extension".to_string(),
+        );
+    }
+}
+
+fn check_invoice_content(
+    report: &mut PreflightReport,
+    content: &[u8],
+    index: Option<usize>,
+    filename: Option<&str>,
+    limits: &KsefLimits,
+) -> Option<InvoiceSchema> {
+    if content.is_empty() {
+        report.error(
+            PreflightIssueKind::EmptyInvoice,
+            index,
+            filename,
+            "invoice is empty".to_string(),
+        );
+        return None;
+    }
+
+    let xml = match std::str::from_utf8(content) {
+        Ok(xml) => xml,
+        Err(e) => {
+            report.error(
+                PreflightIssueKind::InvalidUtf8,
+                index,
+                filename,
+                format!("invoice is not valid UTF-8: {}", e),
+            );
+            return None;
+        }
+    };
+
+    let has_attachments = xml.contains("Zalacznik>") || xml.contains("Zalacznik ");
+    let limit = if has_attachments {
+        limits.max_invoice_size_with_attachments
+    } else {
+        limits.max_invoice_size
+    };
+    if content.len() > limit {
+        report.error(
+            PreflightIssueKind::InvoiceTooLarge,
+            index,
+            filename,
+            format!("invoice has {} bytes, limit is {}", content.len(), limit),
+        );
+    }
+
+    if let Err(e) = check_well_formed(xml) {
+        report.error(
+            PreflightIssueKind::MalformedXml,
+            index,
+            filename,
+            format!("invoice is not well-formed XML: {}", e),
+        );
+        return None;
+    }
+
+    match InvoiceSchema::detect(content) {
+        Ok(schema) => Some(schema),
+        Err(e) => {
+            report.error(
+                PreflightIssueKind::UnsupportedSchema,
+                index,
+                filename,
+                e.to_string(),
+            );
+            None
+        }
+    }
+}
+
+fn check_well_formed(xml: &str) -> Result<(), String> {
+    let bytes = xml.as_bytes();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut root_closed = false;
+    let mut seen_root = false;
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] != b'<' {
+            let next = xml[pos..].find('<').map_or(bytes.len(), |i| pos + i);
+            if stack.is_empty() && !xml[pos..next].trim().is_empty() {
+                return Err(format!("text outside of the root element at byte {}", pos));
+            }
+            pos = next;
+            continue;
+        }
+
+        let rest = &xml[pos..];
+        let (end, skip) = if rest.starts_with("<?") {
+            (rest.find("?>"), 2)
+        } else if rest.starts_with("<!--") {
+            (rest.find("-->"), 3)
+        } else if rest.starts_with("<![CDATA[") {
+            if stack.is_empty() {
+                return Err(format!("CDATA outside of the root element at byte {}", pos));
+            }
+            (rest.find("]]>"), 3)
+        } else if rest.starts_with("<!") {
+            (rest.find('>'), 1)
+        } else {
+            (None, 0)
+        };
+
+        if skip > 0 {
+            let end = end.ok_or_else(|| format!("unterminated markup at byte {}", pos))?;
+            pos += end + skip;
+            continue;
+        }
+
+        let tag_end =
+            find_tag_end(rest).ok_or_else(|| format!("unterminated tag at byte {}", pos))?;
+        let tag = &rest[1..tag_end];
+        pos += tag_end + 1;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match stack.pop() {
+                Some(open) if open == name => {
+                    if stack.is_empty() {
+                        root_closed = true;
+                    }
+                }
+                Some(open) => {
+                    return Err(format!("expected </{}>, found </{}>", open, name));
+                }
+                None => return Err(format!("unexpected closing tag </{}>", name)),
+            }
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let name = tag.split(|c: char| c.is_whitespace()).next().unwrap_or("");
+        if name.is_empty() {
+            return Err(format!("empty tag name at byte {}", pos));
+        }
+        if root_closed || (stack.is_empty() && seen_root) {
+            return Err(format!("more than one root element (<{}>)", name));
+        }
+        seen_root = true;
+        if self_closing {
+            if stack.is_empty() {
+                root_closed = true;
+            }
+        } else {
+            stack.push(name);
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err(format!("element <{}> is not closed", open));
+    }
+    if !seen_root {
+        return Err("no root element".to_string());
+    }
+    Ok(())
+}
+
+fn find_tag_end(tag: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FA2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- invoice -->
+<Faktura xmlns="http://crd.gov.pl/wzor/2023/06/29/12648/"><Naglowek a="1>2"/><P_1>2025-01-01</P_1></Faktura>"#;
+
+    fn payload(filename: &str, content: &str) -> InvoicePayload {
+        InvoicePayload {
+            filename: filename.to_string(),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_check_invoice() {
+        let limits = KsefLimits::default();
+        assert!(check_invoice(FA2.as_bytes(), &limits).is_ok());
+
+        let report = check_invoice(b"<Faktura><P_1></Faktura>", &limits);
+        assert!(report.has(PreflightIssueKind::MalformedXml));
+
+        let report = check_invoice(&[0xff, 0xfe], &limits);
+        assert!(report.has(PreflightIssueKind::InvalidUtf8));
+
+        let small = KsefLimits {
+            max_invoice_size: 10,
+            ..KsefLimits::default()
+        };
+        let report = check_invoice(FA2.as_bytes(), &small);
+        assert!(report.has(PreflightIssueKind::InvoiceTooLarge));
+        assert!(report.into_result().is_err());
+    }
+
+    #[test]
+    fn test_check_batch() {
+        let limits = KsefLimits::default();
+        let fa3 = r#"<Faktura xmlns="http://crd.gov.pl/wzor/2025/06/25/13775/"/>"#;
+
+        let report = check_batch(
+            &[
+                payload("a.xml", FA2),
+                payload("a.xml", FA2),
+                payload("../b.xml", FA2),
+                payload("c.txt", FA2),
+                payload("d.xml", fa3),
+            ],
+            &limits,
+        );
+        assert!(report.has(PreflightIssueKind::DuplicateFilename));
+        assert!(report.has(PreflightIssueKind::InvalidFilename));
+        assert!(report.has(PreflightIssueKind::MixedSchemas));
+        assert_eq!(report.warnings().count(), 1);
+        assert!(!report.is_ok());
+
+        assert!(check_batch(&[], &limits).has(PreflightIssueKind::NoInvoices));
+
+        let report = check_batch_plan(200 * MIB, &[101 * MIB, 99 * MIB], &limits);
+        assert!(report.has(PreflightIssueKind::PartTooLarge));
+        assert_eq!(report.errors().count(), 1);
+    }
+}
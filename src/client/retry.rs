@@ -0,0 +1,151 @@
+use crate::client::error::KsefError;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    pub async fn run<T, F, Fut>(&self, operation: F) -> Result<T, KsefError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, KsefError>>,
+    {
+        self.run_if(KsefError::is_transient, operation).await
+    }
+
+    pub async fn run_if<T, F, Fut, P>(
+        &self,
+        should_retry: P,
+        mut operation: F,
+    ) -> Result<T, KsefError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, KsefError>>,
+        P: Fn(&KsefError) -> bool,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_retries && should_retry(&e) => {
+                    let delay = match &e {
+                        KsefError::RateLimited(Some(retry_after)) => *retry_after,
+                        _ => self.backoff(attempt),
+                    };
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_retries_only_transient_errors() {
+        let policy = RetryPolicy::default().with_backoff(Duration::ZERO, Duration::ZERO);
+
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .run(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(KsefError::RateLimited(None))
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), KsefError> = policy
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(KsefError::ApiErrorRaw(400, "bad request".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_if_uses_predicate() {
+        let policy = RetryPolicy::default().with_backoff(Duration::ZERO, Duration::ZERO);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), KsefError> = policy
+            .run_if(KsefError::is_safe_to_resend, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(KsefError::ApiErrorRaw(500, "internal error".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<(), KsefError> = policy
+            .run_if(KsefError::is_safe_to_resend, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(KsefError::ApiErrorRaw(503, "unavailable".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::KsefClient;
 use crate::client::KsefError;
 use crate::client::routes;
+use crate::client::status_codes::AuthStatusCode;
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
@@ -20,7 +21,7 @@ pub struct Session {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionStatus {
-    pub code: i32,
+    pub code: AuthStatusCode,
     pub description: String,
     pub details: Option<Vec<String>>,
 }
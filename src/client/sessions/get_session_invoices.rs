@@ -0,0 +1,77 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::online_session::get_invoice_status::GetInvoiceStatusResponse;
+use crate::client::routes;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInvoicesResponse {
+    pub continuation_token: Option<String>,
+    pub invoices: Vec<GetInvoiceStatusResponse>,
+}
+
+pub async fn get_session_invoices(
+    client: &KsefClient,
+    reference_number: &str,
+    page_size: Option<i32>,
+    continuation_token: Option<&str>,
+) -> Result<SessionInvoicesResponse, KsefError> {
+    let path = format!("{}/{}/invoices", routes::SESSIONS_PATH, reference_number);
+    query_session_invoices(client, &path, page_size, continuation_token).await
+}
+
+pub async fn get_failed_session_invoices(
+    client: &KsefClient,
+    reference_number: &str,
+    page_size: Option<i32>,
+    continuation_token: Option<&str>,
+) -> Result<SessionInvoicesResponse, KsefError> {
+    let path = format!(
+        "{}/{}/invoices/failed",
+        routes::SESSIONS_PATH,
+        reference_number
+    );
+    query_session_invoices(client, &path, page_size, continuation_token).await
+}
+
+async fn query_session_invoices(
+    client: &KsefClient,
+    path: &str,
+    page_size: Option<i32>,
+    continuation_token: Option<&str>,
+) -> Result<SessionInvoicesResponse, KsefError> {
+    let url = client.url_for(path);
+
+    let access_token = KsefClient::secret_str(&client.access_token.access_token);
+    if access_token.is_empty() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "No access token available".to_string(),
+        ));
+    }
+
+    let mut req = client
+        .client
+        .get(&url)
+        .header("Accept", "application/json")
+        .bearer_auth(access_token);
+
+    if let Some(size) = page_size {
+        req = req.query(&[("pageSize", size)]);
+    }
+    if let Some(token) = continuation_token {
+        req = req.header("x-continuation-token", token);
+    }
+
+    let resp = req.send().await.map_err(KsefError::RequestError)?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(status.as_u16(), body));
+    }
+
+    let parsed: SessionInvoicesResponse = resp.json().await.map_err(KsefError::RequestError)?;
+    Ok(parsed)
+}
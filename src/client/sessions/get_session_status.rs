@@ -0,0 +1,66 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::routes;
+use crate::client::sessions::InvoiceSessionStatus;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatusResponse {
+    pub status: InvoiceSessionStatus,
+    pub date_created: DateTime<Utc>,
+    pub date_updated: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub upo: Option<SessionUpo>,
+    pub invoice_count: Option<i32>,
+    pub successful_invoice_count: Option<i32>,
+    pub failed_invoice_count: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUpo {
+    pub pages: Vec<SessionUpoPage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUpoPage {
+    pub reference_number: String,
+    pub download_url: String,
+    pub download_url_expiration_date: DateTime<Utc>,
+}
+
+pub async fn get_session_status(
+    client: &KsefClient,
+    reference_number: &str,
+) -> Result<SessionStatusResponse, KsefError> {
+    let url = client.url_for(&format!("{}/{}", routes::SESSIONS_PATH, reference_number));
+
+    let access_token = KsefClient::secret_str(&client.access_token.access_token);
+    if access_token.is_empty() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "No access token available".to_string(),
+        ));
+    }
+
+    let resp = client
+        .client
+        .get(&url)
+        .header("Accept", "application/json")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(KsefError::RequestError)?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(status.as_u16(), body));
+    }
+
+    let parsed: SessionStatusResponse = resp.json().await.map_err(KsefError::RequestError)?;
+    Ok(parsed)
+}
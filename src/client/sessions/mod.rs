@@ -1,6 +1,20 @@
+use crate::client::status_codes::SessionStatusCode;
+use serde::Deserialize;
+
 pub mod get_active_sessions;
 pub use get_active_sessions::{
     AuthenticationMethod, QuerySessionsResponse, Session, SessionStatus,
 };
+pub mod get_session_invoices;
+pub mod get_session_status;
+pub mod query_sessions;
 pub mod revoke_current_session;
 pub mod revoke_session;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceSessionStatus {
+    pub code: SessionStatusCode,
+    pub description: String,
+    pub details: Option<Vec<String>>,
+}
@@ -0,0 +1,265 @@
+use crate::client::KsefClient;
+use crate::client::error::KsefError;
+use crate::client::routes;
+use crate::client::sessions::InvoiceSessionStatus;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionType {
+    Online,
+    Batch,
+}
+
+impl SessionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionType::Online => "Online",
+            SessionType::Batch => "Batch",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionProcessingStatus {
+    InProgress,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl SessionProcessingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionProcessingStatus::InProgress => "InProgress",
+            SessionProcessingStatus::Succeeded => "Succeeded",
+            SessionProcessingStatus::Failed => "Failed",
+            SessionProcessingStatus::Cancelled => "Cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryInvoiceSessionsRequest {
+    pub session_type: SessionType,
+    pub reference_number: Option<String>,
+    pub date_created_from: Option<DateTime<Utc>>,
+    pub date_created_to: Option<DateTime<Utc>>,
+    pub date_closed_from: Option<DateTime<Utc>>,
+    pub date_closed_to: Option<DateTime<Utc>>,
+    pub date_modified_from: Option<DateTime<Utc>>,
+    pub date_modified_to: Option<DateTime<Utc>>,
+    pub statuses: Vec<SessionProcessingStatus>,
+}
+
+impl QueryInvoiceSessionsRequest {
+    pub fn builder() -> QueryInvoiceSessionsRequestBuilder {
+        QueryInvoiceSessionsRequestBuilder::new()
+    }
+
+    fn to_query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("sessionType", self.session_type.as_str().to_string())];
+
+        if let Some(reference_number) = &self.reference_number {
+            params.push(("referenceNumber", reference_number.clone()));
+        }
+
+        let dates = [
+            ("dateCreatedFrom", self.date_created_from),
+            ("dateCreatedTo", self.date_created_to),
+            ("dateClosedFrom", self.date_closed_from),
+            ("dateClosedTo", self.date_closed_to),
+            ("dateModifiedFrom", self.date_modified_from),
+            ("dateModifiedTo", self.date_modified_to),
+        ];
+        for (name, date) in dates {
+            if let Some(date) = date {
+                params.push((name, date.to_rfc3339_opts(SecondsFormat::Millis, true)));
+            }
+        }
+
+        for status in &self.statuses {
+            params.push(("statuses", status.as_str().to_string()));
+        }
+
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QueryInvoiceSessionsRequestBuilder {
+    session_type: Option<SessionType>,
+    reference_number: Option<String>,
+    date_created_from: Option<DateTime<Utc>>,
+    date_created_to: Option<DateTime<Utc>>,
+    date_closed_from: Option<DateTime<Utc>>,
+    date_closed_to: Option<DateTime<Utc>>,
+    date_modified_from: Option<DateTime<Utc>>,
+    date_modified_to: Option<DateTime<Utc>>,
+    statuses: Vec<SessionProcessingStatus>,
+}
+
+impl QueryInvoiceSessionsRequestBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn session_type(mut self, session_type: SessionType) -> Self {
+        self.session_type = Some(session_type);
+        self
+    }
+
+    pub fn reference_number(mut self, v: impl Into<String>) -> Self {
+        self.reference_number = Some(v.into());
+        self
+    }
+
+    pub fn date_created_from(mut self, date: DateTime<Utc>) -> Self {
+        self.date_created_from = Some(date);
+        self
+    }
+
+    pub fn date_created_to(mut self, date: DateTime<Utc>) -> Self {
+        self.date_created_to = Some(date);
+        self
+    }
+
+    pub fn date_closed_from(mut self, date: DateTime<Utc>) -> Self {
+        self.date_closed_from = Some(date);
+        self
+    }
+
+    pub fn date_closed_to(mut self, date: DateTime<Utc>) -> Self {
+        self.date_closed_to = Some(date);
+        self
+    }
+
+    pub fn date_modified_from(mut self, date: DateTime<Utc>) -> Self {
+        self.date_modified_from = Some(date);
+        self
+    }
+
+    pub fn date_modified_to(mut self, date: DateTime<Utc>) -> Self {
+        self.date_modified_to = Some(date);
+        self
+    }
+
+    pub fn status(mut self, status: SessionProcessingStatus) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    pub fn statuses(mut self, statuses: Vec<SessionProcessingStatus>) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    pub fn build(self) -> Result<QueryInvoiceSessionsRequest, &'static str> {
+        let session_type = self.session_type.ok_or("session_type is required")?;
+        Ok(QueryInvoiceSessionsRequest {
+            session_type,
+            reference_number: self.reference_number,
+            date_created_from: self.date_created_from,
+            date_created_to: self.date_created_to,
+            date_closed_from: self.date_closed_from,
+            date_closed_to: self.date_closed_to,
+            date_modified_from: self.date_modified_from,
+            date_modified_to: self.date_modified_to,
+            statuses: self.statuses,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryInvoiceSessionsResponse {
+    pub continuation_token: Option<String>,
+    pub sessions: Vec<InvoiceSession>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceSession {
+    pub reference_number: String,
+    pub status: InvoiceSessionStatus,
+    pub date_created: DateTime<Utc>,
+    pub date_updated: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub total_invoice_count: Option<i32>,
+    pub successful_invoice_count: Option<i32>,
+    pub failed_invoice_count: Option<i32>,
+}
+
+pub async fn query_sessions(
+    client: &KsefClient,
+    request: &QueryInvoiceSessionsRequest,
+    page_size: Option<i32>,
+    continuation_token: Option<&str>,
+) -> Result<QueryInvoiceSessionsResponse, KsefError> {
+    let url = client.url_for(routes::SESSIONS_PATH);
+
+    let access_token = KsefClient::secret_str(&client.access_token.access_token);
+    if access_token.is_empty() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "No access token available".to_string(),
+        ));
+    }
+
+    let mut query_params = request.to_query_params();
+    if let Some(size) = page_size {
+        query_params.push(("pageSize", size.to_string()));
+    }
+
+    let mut req = client
+        .client
+        .get(&url)
+        .query(&query_params)
+        .header("Accept", "application/json")
+        .bearer_auth(access_token);
+
+    if let Some(token) = continuation_token {
+        req = req.header("x-continuation-token", token);
+    }
+
+    let resp = req.send().await.map_err(KsefError::RequestError)?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(status.as_u16(), body));
+    }
+
+    let parsed: QueryInvoiceSessionsResponse =
+        resp.json().await.map_err(KsefError::RequestError)?;
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_query_params() {
+        let request = QueryInvoiceSessionsRequest::builder()
+            .session_type(SessionType::Batch)
+            .date_created_from(Utc.with_ymd_and_hms(2025, 10, 1, 0, 0, 0).unwrap())
+            .status(SessionProcessingStatus::InProgress)
+            .status(SessionProcessingStatus::Failed)
+            .build()
+            .expect("Failed to build request");
+
+        let params = request.to_query_params();
+        assert_eq!(params[0], ("sessionType", "Batch".to_string()));
+        assert!(params.contains(&("dateCreatedFrom", "2025-10-01T00:00:00.000Z".to_string())));
+        assert!(params.contains(&("statuses", "InProgress".to_string())));
+        assert!(params.contains(&("statuses", "Failed".to_string())));
+        assert!(!params.iter().any(|(name, _)| *name == "dateClosedFrom"));
+    }
+
+    #[test]
+    fn test_session_type_is_required() {
+        assert!(QueryInvoiceSessionsRequest::builder().build().is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+macro_rules! define_status_code {
+    (
+        $name:ident,
+        in_progress: [$($progress:ident),*],
+        success: [$($success:ident),*],
+        { $($variant:ident = $code:literal),* $(,)? }
+    ) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "i32", into = "i32")]
+        pub enum $name {
+            $($variant,)*
+            Unknown(i32),
+        }
+
+        impl $name {
+            pub fn from_code(code: i32) -> Self {
+                match code {
+                    $($code => Self::$variant,)*
+                    other => Self::Unknown(other),
+                }
+            }
+
+            pub fn code(&self) -> i32 {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Unknown(code) => *code,
+                }
+            }
+
+            pub fn is_in_progress(&self) -> bool {
+                matches!(self, $(Self::$progress)|*)
+            }
+
+            pub fn is_final(&self) -> bool {
+                !self.is_in_progress()
+            }
+
+            pub fn is_success(&self) -> bool {
+                matches!(self, $(Self::$success)|*)
+            }
+
+            pub fn is_error(&self) -> bool {
+                self.is_final() && !self.is_success()
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(code: i32) -> Self {
+                Self::from_code(code)
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(status: $name) -> Self {
+                status.code()
+            }
+        }
+
+        impl PartialEq<i32> for $name {
+            fn eq(&self, other: &i32) -> bool {
+                self.code() == *other
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.code())
+            }
+        }
+    };
+}
+
+define_status_code!(
+    InvoiceStatusCode,
+    in_progress: [Received, Processing],
+    success: [Accepted],
+    {
+        Received = 100,
+        Processing = 150,
+        Accepted = 200,
+        SessionCancelled = 405,
+        InsufficientPermissions = 410,
+        AttachmentsNotAllowed = 415,
+        ValidationError = 430,
+        DecryptionError = 435,
+        Duplicate = 440,
+        SemanticError = 450,
+        UnknownError = 500,
+        CancelledBySystem = 550,
+    }
+);
+
+impl InvoiceStatusCode {
+    pub fn is_duplicate(&self) -> bool {
+        matches!(self, Self::Duplicate)
+    }
+}
+
+define_status_code!(
+    SessionStatusCode,
+    in_progress: [Open, Processing, Closed],
+    success: [Processed],
+    {
+        Open = 100,
+        Processing = 150,
+        Closed = 170,
+        Processed = 200,
+        PackageValidationError = 405,
+        KeyDecryptionError = 415,
+        InvoiceLimitExceeded = 420,
+        DecompressionError = 430,
+        PartDecryptionError = 435,
+        Cancelled = 440,
+        NoValidInvoices = 445,
+        UnknownError = 500,
+    }
+);
+
+define_status_code!(
+    ExportStatusCode,
+    in_progress: [InProgress],
+    success: [Completed],
+    {
+        InProgress = 100,
+        Completed = 200,
+        Expired = 210,
+        KeyDecryptionError = 415,
+        DateRangeOutOfScope = 420,
+        UnknownError = 500,
+        CancelledBySystem = 550,
+    }
+);
+
+define_status_code!(
+    AuthStatusCode,
+    in_progress: [InProgress],
+    success: [Succeeded],
+    {
+        InProgress = 100,
+        Succeeded = 200,
+        NoPermissions = 415,
+        Revoked = 425,
+        InvalidToken = 450,
+        CertificateError = 460,
+        SignatureError = 470,
+        Blocked = 480,
+        UnknownError = 500,
+        CancelledBySystem = 550,
+    }
+);
+
+define_status_code!(
+    EnrollmentStatusCode,
+    in_progress: [InProgress],
+    success: [Completed],
+    {
+        InProgress = 100,
+        Completed = 200,
+        Rejected = 400,
+        UnknownError = 500,
+        CancelledBySystem = 550,
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invoice_status_code_helpers() {
+        assert!(InvoiceStatusCode::Received.is_in_progress());
+        assert!(InvoiceStatusCode::Processing.is_in_progress());
+        assert!(InvoiceStatusCode::Accepted.is_final());
+        assert!(InvoiceStatusCode::Accepted.is_success());
+        assert!(InvoiceStatusCode::Duplicate.is_duplicate());
+        assert!(InvoiceStatusCode::Duplicate.is_error());
+        assert!(InvoiceStatusCode::Unknown(999).is_final());
+        assert!(!InvoiceStatusCode::Unknown(999).is_success());
+        assert_eq!(
+            InvoiceStatusCode::from_code(450),
+            InvoiceStatusCode::SemanticError
+        );
+        assert_eq!(InvoiceStatusCode::Accepted, 200);
+    }
+
+    #[test]
+    fn test_status_code_serde_roundtrip() {
+        let code: SessionStatusCode = serde_json::from_str("170").unwrap();
+        assert_eq!(code, SessionStatusCode::Closed);
+        assert!(code.is_in_progress());
+        assert!(SessionStatusCode::Processed.is_success());
+
+        let code: ExportStatusCode = serde_json::from_str("777").unwrap();
+        assert_eq!(code, ExportStatusCode::Unknown(777));
+        assert_eq!(serde_json::to_string(&code).unwrap(), "777");
+
+        let code: AuthStatusCode = serde_json::from_str("425").unwrap();
+        assert_eq!(code, AuthStatusCode::Revoked);
+        assert!(code.is_error());
+
+        let code: EnrollmentStatusCode = serde_json::from_str("100").unwrap();
+        assert!(code.is_in_progress());
+    }
+}
@@ -1,20 +1,32 @@
 use crate::client::KsefClient;
+use crate::client::archive::store::{ArchiveEntry, InvoiceArchive};
 use crate::client::error::KsefError;
 use crate::client::fetching_invoices;
+use crate::client::fetching_invoices::date_range_split::WindowedExportResult;
+use crate::client::fetching_invoices::download_export::{ExportDownload, ExportDownloadOptions};
+use crate::client::fetching_invoices::export_handle::ExportHandle;
 use crate::client::fetching_invoices::export_invoices::{
     ExportInvoicesRequest, ExportInvoicesResponse, ExportInvoicesStatusResponse, ExportResult,
 };
 use crate::client::fetching_invoices::fetch_invoice::FetchInvoiceResponse;
 use crate::client::fetching_invoices::fetch_invoice_metadata::{
-    FetchInvoiceMetadataRequest, FetchInvoiceMetadataResponse, QueryCriteria, SubjectType,
+    FetchInvoiceMetadataRequest, FetchInvoiceMetadataResponse, InvoiceMetadata, QueryCriteria,
+    SubjectType,
 };
-use crate::client::fetching_invoices::incremental_fetch::{FetchedInvoice, IncrementalFetchState};
+use crate::client::fetching_invoices::fetch_invoices::{FetchInvoicesItem, FetchInvoicesOptions};
+use crate::client::fetching_invoices::incremental_fetch::{
+    FetchedInvoices, IncrementalArchiveResult, IncrementalFetchOptions, IncrementalFetchState,
+};
+use crate::client::fetching_invoices::state_store::{SeenStore, StateStore};
 use crate::client::types::{KsefNumber, ReferenceNumber};
 use crate::client::upo;
 use crate::client::upo::get_invoice_upo_by_ksef_number::{
     GetInvoiceUpoResponse, InvoiceIdentifier,
 };
+use crate::client::upo::get_session_upo::{GetSessionUpoResponse, SavedSessionUpoPage};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::path::Path;
 
 #[async_trait]
 pub trait KsefInvoices {
@@ -24,16 +36,61 @@ pub trait KsefInvoices {
         identifier: InvoiceIdentifier,
     ) -> Result<GetInvoiceUpoResponse, KsefError>;
 
+    async fn get_session_upo(
+        &self,
+        reference_number: ReferenceNumber,
+        upo_reference_number: &str,
+    ) -> Result<GetSessionUpoResponse, KsefError>;
+
+    async fn download_session_upo(
+        &self,
+        reference_number: ReferenceNumber,
+        dir: &Path,
+    ) -> Result<Vec<SavedSessionUpoPage>, KsefError>;
+
     async fn fetch_invoice_metadata(
         &self,
         request: FetchInvoiceMetadataRequest,
     ) -> Result<FetchInvoiceMetadataResponse, KsefError>;
 
+    fn stream_invoice_metadata(
+        &self,
+        query: QueryCriteria,
+    ) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>>;
+
+    fn stream_invoice_metadata_in_windows(
+        &self,
+        query: QueryCriteria,
+    ) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>>;
+
     async fn fetch_invoice(
         &self,
         ksef_number: KsefNumber,
     ) -> Result<FetchInvoiceResponse, KsefError>;
 
+    async fn fetch_invoice_for_metadata(
+        &self,
+        metadata: &InvoiceMetadata,
+    ) -> Result<FetchInvoiceResponse, KsefError>;
+
+    async fn fetch_invoice_into_archive(
+        &self,
+        metadata: &InvoiceMetadata,
+        archive: &dyn InvoiceArchive,
+    ) -> Result<ArchiveEntry, KsefError>;
+
+    fn fetch_invoices(
+        &self,
+        ksef_numbers: Vec<KsefNumber>,
+        concurrency: usize,
+    ) -> BoxStream<'_, FetchInvoicesItem>;
+
+    fn fetch_invoices_with_options(
+        &self,
+        ksef_numbers: Vec<KsefNumber>,
+        options: FetchInvoicesOptions,
+    ) -> BoxStream<'_, FetchInvoicesItem>;
+
     async fn start_export_invoices(
         &self,
         request: ExportInvoicesRequest,
@@ -46,13 +103,83 @@ pub trait KsefInvoices {
 
     async fn export_invoices(&self, query: QueryCriteria) -> Result<ExportResult, KsefError>;
 
+    async fn export_invoices_in_windows(
+        &self,
+        query: QueryCriteria,
+    ) -> Result<WindowedExportResult, KsefError>;
+
+    async fn download_export_to(
+        &self,
+        query: QueryCriteria,
+        dir: &Path,
+    ) -> Result<ExportDownload, KsefError>;
+
+    async fn download_export_to_with_options(
+        &self,
+        query: QueryCriteria,
+        dir: &Path,
+        options: &ExportDownloadOptions,
+    ) -> Result<ExportDownload, KsefError>;
+
+    async fn start_resumable_export(
+        &self,
+        query: QueryCriteria,
+        wrapping_key: &[u8],
+    ) -> Result<ExportHandle, KsefError>;
+
+    async fn resume_export(
+        &self,
+        handle_path: &Path,
+        wrapping_key: &[u8],
+    ) -> Result<ExportResult, KsefError>;
+
+    async fn resume_export_to(
+        &self,
+        handle_path: &Path,
+        wrapping_key: &[u8],
+        dir: &Path,
+        options: &ExportDownloadOptions,
+    ) -> Result<ExportDownload, KsefError>;
+
     async fn export_invoices_incrementally(
         &self,
         state: &mut IncrementalFetchState,
         subject_types: Vec<SubjectType>,
-        window_end: Option<chrono::DateTime<chrono::Utc>>,
         default_start: chrono::DateTime<chrono::Utc>,
-    ) -> Result<Vec<FetchedInvoice>, KsefError>;
+        options: &IncrementalFetchOptions,
+    ) -> Result<FetchedInvoices, KsefError>;
+
+    async fn export_invoices_incrementally_with_store<H>(
+        &self,
+        store: &dyn StateStore,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+        handler: H,
+    ) -> Result<IncrementalFetchState, KsefError>
+    where
+        H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send;
+
+    async fn export_invoices_incrementally_with_options<H>(
+        &self,
+        store: &dyn StateStore,
+        seen: &dyn SeenStore,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+        handler: H,
+    ) -> Result<IncrementalFetchState, KsefError>
+    where
+        H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send;
+
+    async fn export_invoices_incrementally_into_archive(
+        &self,
+        store: &dyn StateStore,
+        archive: &dyn InvoiceArchive,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+    ) -> Result<IncrementalArchiveResult, KsefError>;
 }
 
 #[async_trait]
@@ -66,6 +193,22 @@ impl KsefInvoices for KsefClient {
             .await
     }
 
+    async fn get_session_upo(
+        &self,
+        reference_number: ReferenceNumber,
+        upo_reference_number: &str,
+    ) -> Result<GetSessionUpoResponse, KsefError> {
+        upo::get_session_upo::get_session_upo(self, &reference_number, upo_reference_number).await
+    }
+
+    async fn download_session_upo(
+        &self,
+        reference_number: ReferenceNumber,
+        dir: &Path,
+    ) -> Result<Vec<SavedSessionUpoPage>, KsefError> {
+        upo::get_session_upo::download_session_upo(self, &reference_number, dir).await
+    }
+
     async fn fetch_invoice_metadata(
         &self,
         request: FetchInvoiceMetadataRequest,
@@ -73,6 +216,20 @@ impl KsefInvoices for KsefClient {
         fetching_invoices::fetch_invoice_metadata::fetch_invoice_metadata(self, request).await
     }
 
+    fn stream_invoice_metadata(
+        &self,
+        query: QueryCriteria,
+    ) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>> {
+        fetching_invoices::stream_invoice_metadata::stream_invoice_metadata(self, query)
+    }
+
+    fn stream_invoice_metadata_in_windows(
+        &self,
+        query: QueryCriteria,
+    ) -> BoxStream<'_, Result<InvoiceMetadata, KsefError>> {
+        fetching_invoices::date_range_split::stream_invoice_metadata_in_windows(self, query)
+    }
+
     async fn fetch_invoice(
         &self,
         ksef_number: KsefNumber,
@@ -80,6 +237,37 @@ impl KsefInvoices for KsefClient {
         fetching_invoices::fetch_invoice::fetch_invoice(self, &ksef_number).await
     }
 
+    async fn fetch_invoice_for_metadata(
+        &self,
+        metadata: &InvoiceMetadata,
+    ) -> Result<FetchInvoiceResponse, KsefError> {
+        fetching_invoices::fetch_invoice::fetch_invoice_for_metadata(self, metadata).await
+    }
+
+    async fn fetch_invoice_into_archive(
+        &self,
+        metadata: &InvoiceMetadata,
+        archive: &dyn InvoiceArchive,
+    ) -> Result<ArchiveEntry, KsefError> {
+        fetching_invoices::fetch_invoice::fetch_invoice_into_archive(self, metadata, archive).await
+    }
+
+    fn fetch_invoices(
+        &self,
+        ksef_numbers: Vec<KsefNumber>,
+        concurrency: usize,
+    ) -> BoxStream<'_, FetchInvoicesItem> {
+        fetching_invoices::fetch_invoices::fetch_invoices(self, ksef_numbers, concurrency)
+    }
+
+    fn fetch_invoices_with_options(
+        &self,
+        ksef_numbers: Vec<KsefNumber>,
+        options: FetchInvoicesOptions,
+    ) -> BoxStream<'_, FetchInvoicesItem> {
+        fetching_invoices::fetch_invoices::fetch_invoices_with_options(self, ksef_numbers, options)
+    }
+
     async fn start_export_invoices(
         &self,
         request: ExportInvoicesRequest,
@@ -98,19 +286,144 @@ impl KsefInvoices for KsefClient {
         fetching_invoices::export_invoices::export_invoices(self, query).await
     }
 
+    async fn export_invoices_in_windows(
+        &self,
+        query: QueryCriteria,
+    ) -> Result<WindowedExportResult, KsefError> {
+        fetching_invoices::date_range_split::export_invoices_in_windows(self, query).await
+    }
+
+    async fn download_export_to(
+        &self,
+        query: QueryCriteria,
+        dir: &Path,
+    ) -> Result<ExportDownload, KsefError> {
+        fetching_invoices::download_export::download_export_to(self, query, dir).await
+    }
+
+    async fn download_export_to_with_options(
+        &self,
+        query: QueryCriteria,
+        dir: &Path,
+        options: &ExportDownloadOptions,
+    ) -> Result<ExportDownload, KsefError> {
+        fetching_invoices::download_export::download_export_to_with_options(
+            self, query, dir, options,
+        )
+        .await
+    }
+
+    async fn start_resumable_export(
+        &self,
+        query: QueryCriteria,
+        wrapping_key: &[u8],
+    ) -> Result<ExportHandle, KsefError> {
+        fetching_invoices::export_handle::start_resumable_export(self, query, wrapping_key).await
+    }
+
+    async fn resume_export(
+        &self,
+        handle_path: &Path,
+        wrapping_key: &[u8],
+    ) -> Result<ExportResult, KsefError> {
+        fetching_invoices::export_handle::resume_export(self, handle_path, wrapping_key).await
+    }
+
+    async fn resume_export_to(
+        &self,
+        handle_path: &Path,
+        wrapping_key: &[u8],
+        dir: &Path,
+        options: &ExportDownloadOptions,
+    ) -> Result<ExportDownload, KsefError> {
+        fetching_invoices::export_handle::resume_export_to(
+            self,
+            handle_path,
+            wrapping_key,
+            dir,
+            options,
+        )
+        .await
+    }
+
     async fn export_invoices_incrementally(
         &self,
         state: &mut IncrementalFetchState,
         subject_types: Vec<SubjectType>,
-        window_end: Option<chrono::DateTime<chrono::Utc>>,
         default_start: chrono::DateTime<chrono::Utc>,
-    ) -> Result<Vec<FetchedInvoice>, KsefError> {
+        options: &IncrementalFetchOptions,
+    ) -> Result<FetchedInvoices, KsefError> {
         fetching_invoices::incremental_fetch::fetch_invoices_incrementally(
             self,
             state,
             subject_types,
-            window_end,
             default_start,
+            options,
+        )
+        .await
+    }
+
+    async fn export_invoices_incrementally_with_store<H>(
+        &self,
+        store: &dyn StateStore,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+        handler: H,
+    ) -> Result<IncrementalFetchState, KsefError>
+    where
+        H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send,
+    {
+        fetching_invoices::incremental_fetch::fetch_invoices_incrementally_with_store(
+            self,
+            store,
+            subject_types,
+            default_start,
+            options,
+            handler,
+        )
+        .await
+    }
+
+    async fn export_invoices_incrementally_with_options<H>(
+        &self,
+        store: &dyn StateStore,
+        seen: &dyn SeenStore,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+        handler: H,
+    ) -> Result<IncrementalFetchState, KsefError>
+    where
+        H: FnMut(&SubjectType, FetchedInvoices) -> Result<(), KsefError> + Send,
+    {
+        fetching_invoices::incremental_fetch::fetch_invoices_incrementally_with_options(
+            self,
+            store,
+            seen,
+            subject_types,
+            default_start,
+            options,
+            handler,
+        )
+        .await
+    }
+
+    async fn export_invoices_incrementally_into_archive(
+        &self,
+        store: &dyn StateStore,
+        archive: &dyn InvoiceArchive,
+        subject_types: Vec<SubjectType>,
+        default_start: chrono::DateTime<chrono::Utc>,
+        options: &IncrementalFetchOptions,
+    ) -> Result<IncrementalArchiveResult, KsefError> {
+        fetching_invoices::incremental_fetch::fetch_invoices_incrementally_into_archive(
+            self,
+            store,
+            archive,
+            subject_types,
+            default_start,
+            options,
         )
         .await
     }
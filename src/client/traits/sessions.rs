@@ -4,21 +4,38 @@ use crate::client::batch_session::full_flow::BatchSubmissionResult;
 use crate::client::batch_session::open_batch_session::{
     OpenBatchSessionRequest, OpenBatchSessionResponse,
 };
+use crate::client::batch_session::outcome::{BatchOutcome, BatchOutcomeOptions};
+use crate::client::batch_session::streaming_batch::{BatchPartFile, PreparedBatch};
+use crate::client::batch_session::upload_batch_parts::{
+    BatchPart, BatchUploadOptions, BatchUploadReport,
+};
 use crate::client::batch_session::zip::EncryptedBatchPart;
 use crate::client::error::KsefError;
+use crate::client::journal::{self, JournalResumeResult, SubmissionJournal};
 use crate::client::models::InvoicePayload;
 use crate::client::online_session;
-use crate::client::online_session::encryption::EncryptionData;
+use crate::client::online_session::encryption::{EncryptedInvoice, EncryptionData};
 use crate::client::online_session::full_flow::OnlineSubmissionResult;
 use crate::client::online_session::get_invoice_status::GetInvoiceStatusResponse;
 use crate::client::online_session::open_online_session::{
     OpenOnlineSessionRequest, OpenOnlineSessionResponse,
 };
-use crate::client::online_session::send_invoice::SendInvoiceResponse;
+use crate::client::online_session::send_invoice::{SendInvoiceOptions, SendInvoiceResponse};
+use crate::client::online_session::send_invoices::{SendInvoicesOptions, SentInvoice};
+use crate::client::online_session::watch_invoice_status::{
+    InvoiceStatusUpdate, WatchStatusOptions,
+};
 use crate::client::sessions;
 use crate::client::sessions::QuerySessionsResponse;
+use crate::client::sessions::get_session_invoices::SessionInvoicesResponse;
+use crate::client::sessions::get_session_status::SessionStatusResponse;
+use crate::client::sessions::query_sessions::{
+    QueryInvoiceSessionsRequest, QueryInvoiceSessionsResponse,
+};
 use crate::client::types::ReferenceNumber;
 use async_trait::async_trait;
+use futures::stream::{BoxStream, Stream};
+use std::path::Path;
 
 #[async_trait]
 pub trait KsefSessions {
@@ -56,8 +73,88 @@ pub trait KsefSessions {
         max_part_size_bytes: Option<usize>,
     ) -> Result<BatchSubmissionResult, KsefError>;
 
+    async fn submit_batch_with_options(
+        &self,
+        invoices: &[InvoicePayload],
+        max_part_size_bytes: Option<usize>,
+        options: &SendInvoiceOptions,
+    ) -> Result<BatchSubmissionResult, KsefError>;
+
+    async fn upload_batch_part_files(
+        &self,
+        response: &OpenBatchSessionResponse,
+        parts: &[BatchPartFile],
+    ) -> Result<(), KsefError>;
+
+    async fn get_batch_outcome(
+        &self,
+        reference_number: ReferenceNumber,
+        invoices: &[InvoicePayload],
+        options: &BatchOutcomeOptions,
+    ) -> Result<BatchOutcome, KsefError>;
+
+    async fn upload_batch_parts_with_options<P: BatchPart>(
+        &self,
+        response: &OpenBatchSessionResponse,
+        parts: &[P],
+        options: &BatchUploadOptions,
+    ) -> Result<BatchUploadReport, KsefError>;
+
+    async fn submit_prepared_batch(
+        &self,
+        prepared: &PreparedBatch,
+        encryption_data: &EncryptionData,
+        options: &SendInvoiceOptions,
+    ) -> Result<BatchSubmissionResult, KsefError>;
+
+    async fn submit_batch_from_iter<I>(
+        &self,
+        invoices: I,
+        max_part_size_bytes: Option<usize>,
+    ) -> Result<BatchSubmissionResult, KsefError>
+    where
+        I: IntoIterator<Item = InvoicePayload> + Send + 'static,
+        I::IntoIter: Send;
+
+    async fn submit_batch_from_dir(
+        &self,
+        dir: &Path,
+        max_part_size_bytes: Option<usize>,
+    ) -> Result<BatchSubmissionResult, KsefError>;
+
     async fn submit_online(&self, invoice: &[u8]) -> Result<OnlineSubmissionResult, KsefError>;
 
+    async fn submit_online_with_options(
+        &self,
+        invoice: &[u8],
+        options: &SendInvoiceOptions,
+    ) -> Result<OnlineSubmissionResult, KsefError>;
+
+    async fn submit_online_with_journal(
+        &self,
+        invoice: &[u8],
+        options: &SendInvoiceOptions,
+        journal: &SubmissionJournal,
+    ) -> Result<OnlineSubmissionResult, KsefError>;
+
+    async fn resume_online(
+        &self,
+        journal: &SubmissionJournal,
+    ) -> Result<JournalResumeResult, KsefError>;
+
+    async fn submit_batch_with_journal(
+        &self,
+        invoices: &[InvoicePayload],
+        max_part_size_bytes: Option<usize>,
+        options: &SendInvoiceOptions,
+        journal: &SubmissionJournal,
+    ) -> Result<BatchSubmissionResult, KsefError>;
+
+    async fn resume_batch(
+        &self,
+        journal: &SubmissionJournal,
+    ) -> Result<JournalResumeResult, KsefError>;
+
     async fn send_invoice(
         &self,
         reference_number: ReferenceNumber,
@@ -65,18 +162,89 @@ pub trait KsefSessions {
         encryption_data: &EncryptionData,
     ) -> Result<SendInvoiceResponse, KsefError>;
 
+    async fn send_invoice_with_options(
+        &self,
+        reference_number: ReferenceNumber,
+        invoice_xml: &[u8],
+        encryption_data: &EncryptionData,
+        options: &SendInvoiceOptions,
+    ) -> Result<SendInvoiceResponse, KsefError>;
+
+    async fn send_encrypted_invoice(
+        &self,
+        reference_number: ReferenceNumber,
+        encrypted_invoice: &EncryptedInvoice,
+        options: &SendInvoiceOptions,
+    ) -> Result<SendInvoiceResponse, KsefError>;
+
+    fn send_invoices<'a, S>(
+        &'a self,
+        reference_number: ReferenceNumber,
+        invoices: S,
+        encryption_data: &EncryptionData,
+        options: SendInvoicesOptions,
+    ) -> BoxStream<'a, SentInvoice>
+    where
+        S: Stream<Item = Vec<u8>> + Send + 'a;
+
     async fn get_invoice_status(
         &self,
         session_reference_number: ReferenceNumber,
         invoice_reference_number: ReferenceNumber,
     ) -> Result<GetInvoiceStatusResponse, KsefError>;
 
+    async fn get_invoice_status_once(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_number: ReferenceNumber,
+    ) -> Result<GetInvoiceStatusResponse, KsefError>;
+
+    fn watch_invoice_status(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_number: ReferenceNumber,
+        options: WatchStatusOptions,
+    ) -> BoxStream<'_, Result<GetInvoiceStatusResponse, KsefError>>;
+
+    fn watch_invoice_statuses(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_numbers: Vec<ReferenceNumber>,
+        options: WatchStatusOptions,
+    ) -> BoxStream<'_, InvoiceStatusUpdate>;
+
     async fn close_online_session(
         &self,
         reference_number: ReferenceNumber,
     ) -> Result<(), KsefError>;
 
     async fn generate_encryption_data(&self) -> Result<EncryptionData, KsefError>;
+
+    async fn get_session_status(
+        &self,
+        reference_number: ReferenceNumber,
+    ) -> Result<SessionStatusResponse, KsefError>;
+
+    async fn get_session_invoices(
+        &self,
+        reference_number: ReferenceNumber,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<SessionInvoicesResponse, KsefError>;
+
+    async fn get_failed_session_invoices(
+        &self,
+        reference_number: ReferenceNumber,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<SessionInvoicesResponse, KsefError>;
+
+    async fn query_sessions(
+        &self,
+        request: QueryInvoiceSessionsRequest,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<QueryInvoiceSessionsResponse, KsefError>;
 }
 
 #[async_trait]
@@ -134,10 +302,132 @@ impl KsefSessions for KsefClient {
         batch_session::full_flow::submit_batch(self, invoices, max_part_size_bytes).await
     }
 
+    async fn submit_batch_with_options(
+        &self,
+        invoices: &[InvoicePayload],
+        max_part_size_bytes: Option<usize>,
+        options: &SendInvoiceOptions,
+    ) -> Result<BatchSubmissionResult, KsefError> {
+        batch_session::full_flow::submit_batch_with_options(
+            self,
+            invoices,
+            max_part_size_bytes,
+            options,
+        )
+        .await
+    }
+
+    async fn upload_batch_part_files(
+        &self,
+        response: &OpenBatchSessionResponse,
+        parts: &[BatchPartFile],
+    ) -> Result<(), KsefError> {
+        batch_session::upload_batch_parts::upload_batch_part_files(self, response, parts).await
+    }
+
+    async fn get_batch_outcome(
+        &self,
+        reference_number: ReferenceNumber,
+        invoices: &[InvoicePayload],
+        options: &BatchOutcomeOptions,
+    ) -> Result<BatchOutcome, KsefError> {
+        batch_session::outcome::get_batch_outcome(self, &reference_number, invoices, options).await
+    }
+
+    async fn upload_batch_parts_with_options<P: BatchPart>(
+        &self,
+        response: &OpenBatchSessionResponse,
+        parts: &[P],
+        options: &BatchUploadOptions,
+    ) -> Result<BatchUploadReport, KsefError> {
+        batch_session::upload_batch_parts::upload_batch_parts_with_options(
+            self, response, parts, options,
+        )
+        .await
+    }
+
+    async fn submit_prepared_batch(
+        &self,
+        prepared: &PreparedBatch,
+        encryption_data: &EncryptionData,
+        options: &SendInvoiceOptions,
+    ) -> Result<BatchSubmissionResult, KsefError> {
+        batch_session::streaming_batch::submit_prepared_batch(
+            self,
+            prepared,
+            encryption_data,
+            options,
+        )
+        .await
+    }
+
+    async fn submit_batch_from_iter<I>(
+        &self,
+        invoices: I,
+        max_part_size_bytes: Option<usize>,
+    ) -> Result<BatchSubmissionResult, KsefError>
+    where
+        I: IntoIterator<Item = InvoicePayload> + Send + 'static,
+        I::IntoIter: Send,
+    {
+        batch_session::streaming_batch::submit_batch_from_iter(self, invoices, max_part_size_bytes)
+            .await
+    }
+
+    async fn submit_batch_from_dir(
+        &self,
+        dir: &Path,
+        max_part_size_bytes: Option<usize>,
+    ) -> Result<BatchSubmissionResult, KsefError> {
+        batch_session::streaming_batch::submit_batch_from_dir(self, dir, max_part_size_bytes).await
+    }
+
     async fn submit_online(&self, invoice: &[u8]) -> Result<OnlineSubmissionResult, KsefError> {
         online_session::full_flow::submit_online(self, invoice).await
     }
 
+    async fn submit_online_with_options(
+        &self,
+        invoice: &[u8],
+        options: &SendInvoiceOptions,
+    ) -> Result<OnlineSubmissionResult, KsefError> {
+        online_session::full_flow::submit_online_with_options(self, invoice, options).await
+    }
+
+    async fn submit_online_with_journal(
+        &self,
+        invoice: &[u8],
+        options: &SendInvoiceOptions,
+        journal: &SubmissionJournal,
+    ) -> Result<OnlineSubmissionResult, KsefError> {
+        journal::submit_online_with_journal(self, invoice, options, journal).await
+    }
+
+    async fn resume_online(
+        &self,
+        journal: &SubmissionJournal,
+    ) -> Result<JournalResumeResult, KsefError> {
+        journal::resume_online(self, journal).await
+    }
+
+    async fn submit_batch_with_journal(
+        &self,
+        invoices: &[InvoicePayload],
+        max_part_size_bytes: Option<usize>,
+        options: &SendInvoiceOptions,
+        journal: &SubmissionJournal,
+    ) -> Result<BatchSubmissionResult, KsefError> {
+        journal::submit_batch_with_journal(self, invoices, max_part_size_bytes, options, journal)
+            .await
+    }
+
+    async fn resume_batch(
+        &self,
+        journal: &SubmissionJournal,
+    ) -> Result<JournalResumeResult, KsefError> {
+        journal::resume_batch(self, journal).await
+    }
+
     async fn send_invoice(
         &self,
         reference_number: ReferenceNumber,
@@ -153,6 +443,57 @@ impl KsefSessions for KsefClient {
         .await
     }
 
+    async fn send_invoice_with_options(
+        &self,
+        reference_number: ReferenceNumber,
+        invoice_xml: &[u8],
+        encryption_data: &EncryptionData,
+        options: &SendInvoiceOptions,
+    ) -> Result<SendInvoiceResponse, KsefError> {
+        online_session::send_invoice::send_invoice_with_options(
+            self,
+            &reference_number,
+            invoice_xml,
+            encryption_data,
+            options,
+        )
+        .await
+    }
+
+    async fn send_encrypted_invoice(
+        &self,
+        reference_number: ReferenceNumber,
+        encrypted_invoice: &EncryptedInvoice,
+        options: &SendInvoiceOptions,
+    ) -> Result<SendInvoiceResponse, KsefError> {
+        online_session::send_invoice::send_encrypted_invoice(
+            self,
+            &reference_number,
+            encrypted_invoice,
+            options,
+        )
+        .await
+    }
+
+    fn send_invoices<'a, S>(
+        &'a self,
+        reference_number: ReferenceNumber,
+        invoices: S,
+        encryption_data: &EncryptionData,
+        options: SendInvoicesOptions,
+    ) -> BoxStream<'a, SentInvoice>
+    where
+        S: Stream<Item = Vec<u8>> + Send + 'a,
+    {
+        online_session::send_invoices::send_invoices(
+            self,
+            reference_number,
+            invoices,
+            encryption_data,
+            options,
+        )
+    }
+
     async fn get_invoice_status(
         &self,
         session_reference_number: ReferenceNumber,
@@ -166,6 +507,47 @@ impl KsefSessions for KsefClient {
         .await
     }
 
+    async fn get_invoice_status_once(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_number: ReferenceNumber,
+    ) -> Result<GetInvoiceStatusResponse, KsefError> {
+        online_session::get_invoice_status::get_invoice_status_once(
+            self,
+            session_reference_number.as_str(),
+            invoice_reference_number.as_str(),
+        )
+        .await
+    }
+
+    fn watch_invoice_status(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_number: ReferenceNumber,
+        options: WatchStatusOptions,
+    ) -> BoxStream<'_, Result<GetInvoiceStatusResponse, KsefError>> {
+        online_session::watch_invoice_status::watch_invoice_status(
+            self,
+            session_reference_number,
+            invoice_reference_number,
+            options,
+        )
+    }
+
+    fn watch_invoice_statuses(
+        &self,
+        session_reference_number: ReferenceNumber,
+        invoice_reference_numbers: Vec<ReferenceNumber>,
+        options: WatchStatusOptions,
+    ) -> BoxStream<'_, InvoiceStatusUpdate> {
+        online_session::watch_invoice_status::watch_invoice_statuses(
+            self,
+            session_reference_number,
+            invoice_reference_numbers,
+            options,
+        )
+    }
+
     async fn close_online_session(
         &self,
         reference_number: ReferenceNumber,
@@ -176,4 +558,51 @@ impl KsefSessions for KsefClient {
     async fn generate_encryption_data(&self) -> Result<EncryptionData, KsefError> {
         online_session::encryption::generate_encryption_data(self).await
     }
+
+    async fn get_session_status(
+        &self,
+        reference_number: ReferenceNumber,
+    ) -> Result<SessionStatusResponse, KsefError> {
+        sessions::get_session_status::get_session_status(self, reference_number.as_str()).await
+    }
+
+    async fn get_session_invoices(
+        &self,
+        reference_number: ReferenceNumber,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<SessionInvoicesResponse, KsefError> {
+        sessions::get_session_invoices::get_session_invoices(
+            self,
+            reference_number.as_str(),
+            page_size,
+            continuation_token,
+        )
+        .await
+    }
+
+    async fn get_failed_session_invoices(
+        &self,
+        reference_number: ReferenceNumber,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<SessionInvoicesResponse, KsefError> {
+        sessions::get_session_invoices::get_failed_session_invoices(
+            self,
+            reference_number.as_str(),
+            page_size,
+            continuation_token,
+        )
+        .await
+    }
+
+    async fn query_sessions(
+        &self,
+        request: QueryInvoiceSessionsRequest,
+        page_size: Option<i32>,
+        continuation_token: Option<&str>,
+    ) -> Result<QueryInvoiceSessionsResponse, KsefError> {
+        sessions::query_sessions::query_sessions(self, &request, page_size, continuation_token)
+            .await
+    }
 }
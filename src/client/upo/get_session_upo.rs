@@ -0,0 +1,173 @@
+use crate::client::KsefClient;
+use crate::client::error::{IntegrityCheck, KsefError};
+use crate::client::fs::write_atomically;
+use crate::client::routes;
+use crate::client::sessions::get_session_status::get_session_status;
+use crate::client::types::ReferenceNumber;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use openssl::sha::sha256;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetSessionUpoResponse {
+    pub content: Vec<u8>,
+
+    pub hash: String,
+}
+
+impl GetSessionUpoResponse {
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.content)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SavedSessionUpoPage {
+    pub reference_number: String,
+    pub path: PathBuf,
+    pub hash: String,
+}
+
+pub async fn get_session_upo(
+    client: &KsefClient,
+    reference_number: &ReferenceNumber,
+    upo_reference_number: &str,
+) -> Result<GetSessionUpoResponse, KsefError> {
+    let url = client.url_for(&format!(
+        "{}/{}/upo/{}",
+        routes::SESSIONS_PATH,
+        reference_number,
+        upo_reference_number
+    ));
+
+    let access_token = KsefClient::secret_str(&client.access_token.access_token);
+    if access_token.is_empty() {
+        return Err(KsefError::ApplicationError(
+            0,
+            "No access token available. Please authenticate and redeem token first.".to_string(),
+        ));
+    }
+
+    let resp = client
+        .client
+        .get(&url)
+        .header("Accept", "application/xml")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(KsefError::RequestError)?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(KsefError::from_api_response(status.as_u16(), body));
+    }
+
+    let hash = resp
+        .headers()
+        .get("x-ms-meta-hash")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+
+    let content = resp
+        .bytes()
+        .await
+        .map_err(KsefError::RequestError)?
+        .to_vec();
+
+    Ok(GetSessionUpoResponse { content, hash })
+}
+
+pub async fn download_session_upo(
+    client: &KsefClient,
+    reference_number: &ReferenceNumber,
+    dir: &Path,
+) -> Result<Vec<SavedSessionUpoPage>, KsefError> {
+    let session_status = get_session_status(client, reference_number.as_str()).await?;
+
+    let pages = match session_status.upo {
+        Some(upo) if !upo.pages.is_empty() => upo.pages,
+        _ => {
+            return Err(KsefError::ApplicationError(
+                0,
+                format!(
+                    "No UPO available for session {} (status {} - {})",
+                    reference_number, session_status.status.code, session_status.status.description
+                ),
+            ));
+        }
+    };
+
+    tokio::fs::create_dir_all(dir).await?;
+
+    let mut saved = Vec::with_capacity(pages.len());
+    for page in pages {
+        let upo = get_session_upo(client, reference_number, &page.reference_number).await?;
+
+        let computed_hash = verified_page_hash(&page.reference_number, &upo)?;
+
+        let path = dir.join(format!("{}.xml", page.reference_number));
+        write_atomically(&path, &upo.content).await?;
+        write_atomically(
+            &dir.join(format!("{}.sha256", page.reference_number)),
+            computed_hash.as_bytes(),
+        )
+        .await?;
+
+        saved.push(SavedSessionUpoPage {
+            reference_number: page.reference_number,
+            path,
+            hash: computed_hash,
+        });
+    }
+
+    Ok(saved)
+}
+
+fn verified_page_hash(
+    page_reference_number: &str,
+    upo: &GetSessionUpoResponse,
+) -> Result<String, KsefError> {
+    let computed_hash = BASE64.encode(sha256(&upo.content));
+    if !upo.hash.is_empty() && upo.hash != computed_hash {
+        return Err(KsefError::integrity(
+            format!("session UPO page {}", page_reference_number),
+            IntegrityCheck::Hash,
+            &upo.hash,
+            &computed_hash,
+        ));
+    }
+    Ok(computed_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verified_page_hash() {
+        let content = b"<Potwierdzenie/>".to_vec();
+        let hash = BASE64.encode(sha256(&content));
+        let upo = GetSessionUpoResponse {
+            content,
+            hash: hash.clone(),
+        };
+        assert_eq!(verified_page_hash("UPO-1", &upo).unwrap(), hash);
+
+        let tampered = GetSessionUpoResponse {
+            content: b"<Potwierdzenie>changed</Potwierdzenie>".to_vec(),
+            hash: hash.clone(),
+        };
+        match verified_page_hash("UPO-1", &tampered) {
+            Err(KsefError::IntegrityError(mismatch)) => {
+                assert_eq!(mismatch.subject, "session UPO page UPO-1");
+                assert_eq!(mismatch.check, IntegrityCheck::Hash);
+                assert_eq!(mismatch.expected, hash);
+            }
+            other => panic!("expected integrity error, got {:?}", other),
+        }
+    }
+}
@@ -1 +1,2 @@
 pub mod get_invoice_upo_by_ksef_number;
+pub mod get_session_upo;
@@ -1,13 +1,19 @@
 mod client;
 
 pub use client::KsefClient;
-pub use client::error::KsefError;
+pub use client::error::{IntegrityCheck, IntegrityMismatch, KsefError};
 pub use secrecy;
 
 pub mod types {
     pub use crate::client::types::*;
 }
 
+pub mod archive {
+    pub use crate::client::archive::file_archive::FileArchive;
+    pub use crate::client::archive::query::ArchiveQuery;
+    pub use crate::client::archive::store::{ArchiveEntry, ArchiveRecord, InvoiceArchive};
+}
+
 pub mod auth {
     pub use crate::client::traits::auth::KsefAuth;
 
@@ -46,15 +52,30 @@ pub mod certificates {
     pub use crate::client::ksef_certificates::revoke_certificate::RevocationReason;
 }
 
+pub mod dates {
+    pub use crate::client::dates::*;
+}
+
 pub mod invoices {
     pub use crate::client::traits::invoices::KsefInvoices;
 
+    pub use crate::client::fetching_invoices::date_range_split::{
+        MAX_QUERY_WINDOW_MONTHS, WindowedExportResult, split_query, validate_date_range,
+    };
+    pub use crate::client::fetching_invoices::download_export::{
+        DownloadedPart, ExportDownload, ExportDownloadOptions, download_export_package,
+    };
+    pub use crate::client::fetching_invoices::export_handle::{
+        EXPORT_HANDLE_VERSION, ExportHandle,
+    };
     pub use crate::client::fetching_invoices::export_invoices::{
         EncryptionInfo, ExportInvoicesRequest, ExportInvoicesResponse,
         ExportInvoicesStatusResponse, ExportPackage, ExportPackagePart, ExportResult, ExportStatus,
-        ExportedPart,
+        ExportWarning, ExportedInvoices, ExportedPart, InvoicePackageMetadata,
+    };
+    pub use crate::client::fetching_invoices::fetch_invoice::{
+        FetchInvoiceResponse, compute_invoice_hash,
     };
-    pub use crate::client::fetching_invoices::fetch_invoice::FetchInvoiceResponse;
     pub use crate::client::fetching_invoices::fetch_invoice_metadata::{
         AmountFilter, AmountType, AuthorizedSubjectMetadata, BuyerIdentifier,
         BuyerIdentifierMetadata, BuyerIdentifierType, BuyerMetadata, DateRange, DateRangeBuilder,
@@ -63,16 +84,37 @@ pub mod invoices {
         InvoicingMode, QueryCriteria, QueryCriteriaBuilder, SellerMetadata, SubjectType,
         ThirdSubjectIdentifier, ThirdSubjectMetadata,
     };
+    pub use crate::client::fetching_invoices::fetch_invoices::{
+        DownloadedInvoice, FetchInvoicesItem, FetchInvoicesOptions,
+    };
     pub use crate::client::fetching_invoices::incremental_fetch::{
-        FetchedInvoice, IncrementalFetchState,
+        ContinuationPoint, FetchedInvoice, FetchedInvoices, INCREMENTAL_FETCH_STATE_VERSION,
+        IncrementalArchiveResult, IncrementalFetchOptions, IncrementalFetchState,
     };
+    pub use crate::client::fetching_invoices::state_store::{
+        FileSeenStore, FileStateStore, MemorySeenStore, SeenStore, StateStore,
+    };
+    pub use crate::client::fetching_invoices::stream_invoice_metadata::METADATA_PAGE_SIZE;
     pub use crate::client::models::{FormCode, InvoicePayload};
 }
 
 pub mod models {
+    pub use crate::client::form_codes::InvoiceSchema;
     pub use crate::client::models::{
         ContextIdentifier, ContextIdentifierType, Encryption, Environment,
     };
+    pub use crate::client::retry::RetryPolicy;
+    pub use crate::client::status_codes::{
+        AuthStatusCode, EnrollmentStatusCode, ExportStatusCode, InvoiceStatusCode,
+        SessionStatusCode,
+    };
+}
+
+pub mod preflight {
+    pub use crate::client::preflight::{
+        BatchPreflight, KsefLimits, PreflightIssue, PreflightIssueKind, PreflightReport,
+        PreflightSeverity, check_batch, check_batch_plan, check_invoice,
+    };
 }
 
 pub mod peppol {
@@ -167,11 +209,27 @@ pub mod permissions {
 pub mod sessions {
     pub use crate::client::traits::sessions::KsefSessions;
 
+    pub use crate::client::sessions::get_session_invoices::SessionInvoicesResponse;
+    pub use crate::client::sessions::get_session_status::{
+        SessionStatusResponse, SessionUpo, SessionUpoPage,
+    };
+    pub use crate::client::sessions::query_sessions::{
+        InvoiceSession, QueryInvoiceSessionsRequest, QueryInvoiceSessionsRequestBuilder,
+        QueryInvoiceSessionsResponse, SessionProcessingStatus, SessionType,
+    };
     pub use crate::client::sessions::{
-        AuthenticationMethod, QuerySessionsResponse, Session, SessionStatus,
+        AuthenticationMethod, InvoiceSessionStatus, QuerySessionsResponse, Session, SessionStatus,
+    };
+
+    pub use crate::client::journal::{
+        JOURNAL_VERSION, JournalInvoice, JournalResumeResult, JournalState, JournalStep,
+        SubmissionJournal, SubmissionKind,
     };
 
-    pub use crate::client::online_session::encryption::EncryptionData;
+    pub use crate::client::online_session::encryption::{
+        EncryptedInvoice, EncryptionData, generate_encryption_data_from_certificate,
+        prepare_encrypted_invoice,
+    };
     pub use crate::client::online_session::full_flow::OnlineSubmissionResult;
     pub use crate::client::online_session::get_invoice_status::{
         GetInvoiceStatusResponse, InvoiceStatus,
@@ -179,13 +237,28 @@ pub mod sessions {
     pub use crate::client::online_session::open_online_session::{
         OpenOnlineSessionRequest, OpenOnlineSessionRequestBuilder, OpenOnlineSessionResponse,
     };
-    pub use crate::client::online_session::send_invoice::SendInvoiceResponse;
+    pub use crate::client::online_session::send_invoice::{
+        SendInvoiceOptions, SendInvoiceResponse,
+    };
+    pub use crate::client::online_session::send_invoices::{SendInvoicesOptions, SentInvoice};
+    pub use crate::client::online_session::watch_invoice_status::{
+        InvoiceStatusUpdate, WatchStatusOptions,
+    };
 
     pub use crate::client::batch_session::full_flow::BatchSubmissionResult;
     pub use crate::client::batch_session::open_batch_session::{
         BatchFile, BatchFilePartInfo, OpenBatchSessionRequest, OpenBatchSessionRequestBuilder,
         OpenBatchSessionResponse, PartUploadRequest,
     };
+    pub use crate::client::batch_session::outcome::{
+        BatchInvoiceOutcome, BatchOutcome, BatchOutcomeOptions,
+    };
+    pub use crate::client::batch_session::streaming_batch::{
+        BatchPartFile, PreparedBatch, StreamingBatchBuilder,
+    };
+    pub use crate::client::batch_session::upload_batch_parts::{
+        BatchPart, BatchUploadOptions, BatchUploadReport, BatchUploadState, PartBody,
+    };
     pub use crate::client::batch_session::zip::{
         BatchZipResult, EncryptedBatchPart, FileMetadata, calculate_invoice_hash, create_zip,
         encrypt_zip_parts, split_zip,
@@ -202,6 +275,7 @@ pub mod upo {
     pub use crate::client::upo::get_invoice_upo_by_ksef_number::{
         GetInvoiceUpoResponse, InvoiceIdentifier,
     };
+    pub use crate::client::upo::get_session_upo::{GetSessionUpoResponse, SavedSessionUpoPage};
 }
 
 pub mod traits {
@@ -0,0 +1,51 @@
+use crate::common;
+use ksef_client::invoices::InvoicePayload;
+use ksef_client::prelude::*;
+use ksef_client::sessions::BatchOutcomeOptions;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_batch_outcome_reconciliation() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let issuer_nip = "5261234567";
+    let invoices: Vec<InvoicePayload> = vec![
+        InvoicePayload {
+            filename: "invoice1.xml".to_string(),
+            content: common::generate_fa2_invoice(issuer_nip).await.into_bytes(),
+        },
+        InvoicePayload {
+            filename: "invoice2.xml".to_string(),
+            content: common::generate_fa2_invoice(issuer_nip).await.into_bytes(),
+        },
+    ];
+
+    let result = client
+        .submit_batch(&invoices, None)
+        .await
+        .expect("Failed to submit batch");
+
+    let options = BatchOutcomeOptions::new()
+        .with_interval(Duration::from_secs(2))
+        .with_timeout(Duration::from_secs(300));
+    let outcome = client
+        .get_batch_outcome(
+            ReferenceNumber::new(result.reference_number),
+            &invoices,
+            &options,
+        )
+        .await
+        .expect("Failed to get batch outcome");
+
+    println!("{}", outcome.to_csv());
+
+    assert_eq!(outcome.invoices.len(), invoices.len());
+    for (invoice, payload) in outcome.invoices.iter().zip(&invoices) {
+        assert_eq!(invoice.filename.as_deref(), Some(payload.filename.as_str()));
+        assert!(!invoice.is_missing(), "No status for {}", payload.filename);
+        if invoice.is_accepted() {
+            assert!(invoice.ksef_number.is_some());
+        }
+    }
+    assert_eq!(outcome.to_csv().lines().count(), invoices.len() + 1);
+}
@@ -0,0 +1,48 @@
+use crate::common;
+use ksef_client::invoices::InvoicePayload;
+use ksef_client::prelude::*;
+use ksef_client::sessions::{JournalStep, SendInvoiceOptions, SubmissionJournal};
+
+#[tokio::test]
+async fn test_batch_journal_resume() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let issuer_nip = "5261234567";
+    let invoices = vec![InvoicePayload {
+        filename: "invoice1.xml".to_string(),
+        content: common::generate_fa2_invoice(issuer_nip).await.into_bytes(),
+    }];
+
+    let dir = std::env::temp_dir().join(format!("ksef-batch-journal-{}", std::process::id()));
+    let journal = SubmissionJournal::new(&dir);
+    journal.remove().await.expect("Failed to clear journal");
+
+    let result = client
+        .submit_batch_with_journal(&invoices, None, &SendInvoiceOptions::default(), &journal)
+        .await
+        .expect("Failed to submit batch with journal");
+
+    let state = journal.load().await.expect("Failed to load journal");
+    assert_eq!(state.step, JournalStep::Closed);
+    assert_eq!(
+        state.session_reference_number.as_deref(),
+        Some(result.reference_number.as_str())
+    );
+
+    let resumed = client
+        .resume_batch(&journal)
+        .await
+        .expect("Failed to resume batch");
+    assert_eq!(resumed.resumed_from, JournalStep::Closed);
+    assert_eq!(resumed.session_reference_number, result.reference_number);
+
+    assert!(
+        client
+            .submit_batch_with_journal(&invoices, None, &SendInvoiceOptions::default(), &journal)
+            .await
+            .is_err(),
+        "A used journal must not be overwritten"
+    );
+
+    journal.remove().await.expect("Failed to remove journal");
+}
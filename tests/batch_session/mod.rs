@@ -1 +1,6 @@
+pub mod batch_outcome;
 pub mod batch_session_flow;
+pub mod journal;
+pub mod preflight;
+pub mod resumable_upload;
+pub mod streaming_batch;
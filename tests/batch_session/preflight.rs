@@ -0,0 +1,39 @@
+use crate::common;
+use ksef_client::invoices::InvoicePayload;
+use ksef_client::preflight::{KsefLimits, PreflightIssueKind, check_batch};
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_batch_preflight_rejects_invalid_batch() {
+    let issuer_nip = "5261234567";
+    let invoice_xml = common::generate_fa2_invoice(issuer_nip).await;
+
+    let valid = vec![InvoicePayload {
+        filename: "invoice1.xml".to_string(),
+        content: invoice_xml.as_bytes().to_vec(),
+    }];
+    assert!(check_batch(&valid, &KsefLimits::default()).is_ok());
+
+    let invalid = vec![
+        valid[0].clone(),
+        valid[0].clone(),
+        InvoicePayload {
+            filename: "broken.xml".to_string(),
+            content: b"<Faktura><P_1></Faktura>".to_vec(),
+        },
+    ];
+
+    let context = ContextIdentifier {
+        id_type: ContextIdentifierType::Nip,
+        value: issuer_nip.to_string(),
+    };
+    let client = KsefClient::new(Environment::Test, context);
+
+    match client.submit_batch(&invalid, None).await {
+        Err(KsefError::PreflightError(report)) => {
+            assert!(report.has(PreflightIssueKind::DuplicateFilename));
+            assert!(report.has(PreflightIssueKind::MalformedXml));
+        }
+        other => panic!("Expected pre-flight error, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,87 @@
+use crate::common;
+use ksef_client::prelude::*;
+use ksef_client::types::ReferenceNumber;
+
+use ksef_client::invoices::InvoicePayload;
+use ksef_client::sessions::{
+    BatchUploadOptions, BatchUploadState, OpenBatchSessionRequestBuilder, create_zip,
+    encrypt_zip_parts, split_zip,
+};
+
+#[tokio::test]
+async fn test_resumable_batch_upload() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let issuer_nip = "5261234567";
+    let mut invoices = Vec::new();
+    for i in 0..3 {
+        let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+        invoices.push(InvoicePayload {
+            filename: format!("invoice{}.xml", i),
+            content: invoice_xml.into_bytes(),
+        });
+    }
+
+    let zip_result = create_zip(&invoices).expect("Failed to create ZIP");
+    let parts = split_zip(&zip_result.content, zip_result.content.len() / 2 + 1);
+
+    let encryption_data = client
+        .generate_encryption_data()
+        .await
+        .expect("Failed to generate encryption data");
+
+    let encrypted_parts = encrypt_zip_parts(
+        &parts,
+        &encryption_data.symmetric_key,
+        &encryption_data.initialization_vector,
+    )
+    .expect("Failed to encrypt parts");
+
+    let mut builder = OpenBatchSessionRequestBuilder::new()
+        .with_batch_file_info(zip_result.metadata.size, &zip_result.metadata.hash)
+        .with_encryption(
+            &encryption_data.encrypted_symmetric_key,
+            &encryption_data.initialization_vector,
+        );
+    for part in encrypted_parts.iter() {
+        builder =
+            builder.add_file_part(part.ordinal_number, part.metadata.size, &part.metadata.hash);
+    }
+
+    let response = client
+        .open_batch_session(builder.build().expect("Failed to build request"))
+        .await
+        .expect("Failed to open batch session");
+
+    let state_file =
+        std::env::temp_dir().join(format!("ksef-upload-{}.json", response.reference_number));
+    let options = BatchUploadOptions::new()
+        .with_concurrency(2)
+        .with_state_file(&state_file);
+
+    let report = client
+        .upload_batch_parts_with_options(&response, &encrypted_parts, &options)
+        .await
+        .expect("Failed to upload batch parts");
+    assert!(report.is_complete());
+    assert_eq!(report.uploaded.len(), encrypted_parts.len());
+
+    let state = BatchUploadState::load(&state_file)
+        .await
+        .expect("Failed to load upload state");
+    assert_eq!(state.completed_ordinals.len(), encrypted_parts.len());
+
+    let resumed = client
+        .upload_batch_parts_with_options(&response, &encrypted_parts, &options)
+        .await
+        .expect("Failed to resume batch upload");
+    assert!(resumed.uploaded.is_empty());
+    assert_eq!(resumed.skipped.len(), encrypted_parts.len());
+
+    client
+        .close_batch_session(ReferenceNumber::new(&response.reference_number))
+        .await
+        .expect("Failed to close batch session");
+
+    std::fs::remove_file(&state_file).expect("Failed to remove upload state");
+}
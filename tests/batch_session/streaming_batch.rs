@@ -0,0 +1,31 @@
+use crate::common;
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_submit_batch_from_dir() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let dir = std::env::temp_dir().join(format!("ksef-streaming-batch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Failed to create invoice directory");
+
+    let issuer_nip = "5261234567";
+    for i in 0..3 {
+        let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+        std::fs::write(dir.join(format!("invoice{}.xml", i)), invoice_xml)
+            .expect("Failed to write invoice");
+    }
+
+    let result = client
+        .submit_batch_from_dir(&dir, Some(1024))
+        .await
+        .expect("Failed to submit batch from directory");
+
+    println!(
+        "submitted batch {}, {} parts, {} bytes total",
+        result.reference_number, result.number_of_parts, result.total_size_bytes
+    );
+    assert!(!result.reference_number.is_empty());
+    assert!(result.number_of_parts >= 1);
+
+    std::fs::remove_dir_all(&dir).expect("Failed to remove invoice directory");
+}
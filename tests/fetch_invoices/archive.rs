@@ -0,0 +1,60 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::archive::{ArchiveQuery, FileArchive, InvoiceArchive};
+use ksef_client::invoices::{DateRangeBuilder, DateType, QueryCriteriaBuilder, SubjectType};
+use ksef_client::prelude::*;
+use ksef_client::types::KsefNumber;
+
+#[tokio::test]
+async fn test_fetch_invoice_into_archive() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Invoicing)
+        .from(now - chrono::Duration::days(30))
+        .to(now + chrono::Duration::hours(1))
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let metadata = match client.stream_invoice_metadata(query).next().await {
+        Some(metadata) => metadata.expect("Failed to fetch invoice metadata"),
+        None => {
+            println!("No invoices to archive");
+            return;
+        }
+    };
+
+    let root = std::env::temp_dir().join(format!("ksef-archive-it-{}", std::process::id()));
+    let archive = FileArchive::new(&root);
+    let entry = client
+        .fetch_invoice_into_archive(&metadata, &archive)
+        .await
+        .expect("Failed to archive invoice");
+    assert_eq!(entry.hash.as_str(), metadata.invoice_hash);
+
+    let found = archive
+        .query(
+            &ArchiveQuery::new()
+                .with_ksef_number(metadata.ksef_number.clone())
+                .with_seller_nip(metadata.seller.nip.clone()),
+        )
+        .await
+        .expect("Failed to query archive");
+    assert_eq!(found.len(), 1);
+
+    let record = archive
+        .get(&KsefNumber::new(metadata.ksef_number.clone()))
+        .await
+        .expect("Failed to read archive")
+        .expect("Invoice should be archived");
+    assert!(String::from_utf8_lossy(&record.content).contains("Faktura"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,55 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::invoices::{
+    DateRangeBuilder, DateType, FetchInvoicesOptions, QueryCriteriaBuilder, SubjectType,
+};
+use ksef_client::prelude::*;
+use ksef_client::types::KsefNumber;
+
+#[tokio::test]
+async fn test_fetch_invoices_to_directory() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Invoicing)
+        .from(now - chrono::Duration::days(30))
+        .to(now + chrono::Duration::hours(1))
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let ksef_numbers: Vec<KsefNumber> = client
+        .stream_invoice_metadata(query)
+        .take(10)
+        .map(|invoice| KsefNumber::new(invoice.expect("Failed to fetch metadata").ksef_number))
+        .collect()
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("ksef-bulk-fetch-{}", std::process::id()));
+    let options = FetchInvoicesOptions::new()
+        .with_concurrency(4)
+        .with_output_dir(&dir);
+
+    for pass in 0..2 {
+        let results: Vec<_> = client
+            .fetch_invoices_with_options(ksef_numbers.clone(), options.clone())
+            .collect()
+            .await;
+        assert_eq!(results.len(), ksef_numbers.len());
+
+        for (ksef_number, result) in results {
+            let invoice =
+                result.unwrap_or_else(|e| panic!("Failed to fetch {}: {}", ksef_number, e));
+            assert_eq!(invoice.skipped, pass == 1);
+            assert!(invoice.path.expect("Invoice should be saved").exists());
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,64 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::invoices::{
+    DateRangeBuilder, DateType, QueryCriteriaBuilder, SubjectType, split_query,
+};
+use ksef_client::prelude::*;
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn test_stream_invoice_metadata_in_windows() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Invoicing)
+        .from(now - chrono::Duration::days(200))
+        .to(now + chrono::Duration::hours(1))
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let windows = split_query(&query).expect("Failed to split query");
+    assert_eq!(windows.len(), 3);
+
+    let mut stream = client.stream_invoice_metadata_in_windows(query);
+    let mut ksef_numbers = HashSet::new();
+    while let Some(invoice) = stream.next().await {
+        let invoice = invoice.expect("Failed to stream invoice metadata");
+        assert!(
+            ksef_numbers.insert(invoice.ksef_number.clone()),
+            "Duplicate invoice {}",
+            invoice.ksef_number
+        );
+    }
+
+    println!("Streamed {} invoices", ksef_numbers.len());
+}
+
+#[tokio::test]
+async fn test_invalid_date_range_is_rejected_before_sending() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Issue)
+        .from(now - chrono::Duration::days(200))
+        .restrict_to_permanent_storage_hwm_date(true)
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let result = client.export_invoices_in_windows(query).await;
+    assert!(matches!(result, Err(KsefError::ApplicationError(0, _))));
+}
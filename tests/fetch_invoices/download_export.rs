@@ -0,0 +1,42 @@
+use crate::common;
+use ksef_client::invoices::{DateRangeBuilder, DateType, QueryCriteriaBuilder, SubjectType};
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_download_export_to() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::PermanentStorage)
+        .from(now - chrono::Duration::days(7))
+        .to(now)
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let dir = std::env::temp_dir().join(format!("ksef-export-{}", std::process::id()));
+    let download = client
+        .download_export_to(query, &dir)
+        .await
+        .expect("Failed to download export");
+
+    for part in &download.parts {
+        let content = std::fs::read(&part.path).expect("Failed to read downloaded part");
+        assert_eq!(content.len() as i64, part.metadata.part_size);
+        zip::ZipArchive::new(std::io::Cursor::new(content)).expect("Part is not a valid ZIP");
+    }
+    assert!(std::fs::read_dir(&dir).unwrap().all(|e| {
+        e.unwrap()
+            .path()
+            .extension()
+            .is_some_and(|ext| ext == "zip")
+    }));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,7 +1,8 @@
 use crate::common;
+use ksef_client::dates::today_in_warsaw;
 use ksef_client::invoices::{
-    DateRangeBuilder, DateType, FetchInvoiceMetadataRequestBuilder, IncrementalFetchState,
-    InvoicePayload, QueryCriteriaBuilder, SubjectType,
+    DateRangeBuilder, DateType, FetchInvoiceMetadataRequestBuilder, IncrementalFetchOptions,
+    IncrementalFetchState, InvoicePayload, QueryCriteriaBuilder, SubjectType,
 };
 use ksef_client::prelude::*;
 use ksef_client::types::KsefNumber;
@@ -54,14 +55,11 @@ async fn test_fetch_invoice_flow() {
     );
 
     let now = chrono::Utc::now();
-    let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
-    let from_date = start_of_day.to_rfc3339();
-    let to_date = (now + chrono::Duration::hours(1)).to_rfc3339();
 
     let date_range = DateRangeBuilder::new()
         .date_type(DateType::Invoicing)
-        .from(from_date.clone())
-        .to(to_date.clone())
+        .from_date(today_in_warsaw())
+        .to(now + chrono::Duration::hours(1))
         .build()
         .expect("Failed to build date_range");
 
@@ -136,6 +134,20 @@ async fn test_fetch_invoice_flow() {
         .await
         .expect("Failed to export invoices and wait for completion");
 
+    let exported_invoices = export_result
+        .invoices()
+        .expect("Failed to unpack exported invoices");
+    for warning in exported_invoices.warnings() {
+        println!("Export warning: {}", warning);
+    }
+    for (metadata, content) in exported_invoices.iter() {
+        assert!(
+            String::from_utf8_lossy(content).contains("</Faktura>"),
+            "Exported XML for {} should contain 'Faktura' tag",
+            metadata.ksef_number
+        );
+    }
+
     let exported_parts = export_result.parts;
     println!("Exported {} parts", exported_parts.len());
 
@@ -166,10 +178,11 @@ async fn test_fetch_invoice_flow() {
     let mut state = IncrementalFetchState::new();
     let subject_types = vec![SubjectType::Subject1];
     let default_start = chrono::Utc::now() - chrono::Duration::days(1);
-    let window_end = Some(chrono::Utc::now() + chrono::Duration::days(1));
+    let options = IncrementalFetchOptions::new()
+        .with_window_end(chrono::Utc::now() + chrono::Duration::days(1));
 
     let fetched_invoices = client
-        .export_invoices_incrementally(&mut state, subject_types, window_end, default_start)
+        .export_invoices_incrementally(&mut state, subject_types, default_start, &options)
         .await
         .expect("Failed to fetch invoices incrementally");
 
@@ -0,0 +1,51 @@
+use crate::common;
+use ksef_client::invoices::{
+    FileStateStore, IncrementalFetchOptions, MemorySeenStore, SubjectType,
+};
+use ksef_client::prelude::*;
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn test_incremental_fetch_skips_seen_invoices() {
+    let client = common::authorize_client().await;
+
+    let path =
+        std::env::temp_dir().join(format!("ksef-incremental-seen-{}.json", std::process::id()));
+    let _ = tokio::fs::remove_file(&path).await;
+    let store = FileStateStore::new(&path);
+    let seen = MemorySeenStore::new();
+
+    let default_start = chrono::Utc::now() - chrono::Duration::days(1);
+    let options = IncrementalFetchOptions::new().with_overlap(chrono::Duration::hours(1));
+
+    let mut delivered = HashSet::new();
+    for run in 0..2 {
+        client
+            .export_invoices_incrementally_with_options(
+                &store,
+                &seen,
+                vec![SubjectType::Subject1],
+                default_start,
+                &options,
+                |_, invoices| {
+                    println!("Run {}: {} invoices", run, invoices.len());
+                    for invoice in invoices {
+                        assert!(
+                            delivered.insert(invoice.metadata.ksef_number.clone()),
+                            "Invoice {} delivered twice",
+                            invoice.metadata.ksef_number
+                        );
+                    }
+                    Ok(())
+                },
+            )
+            .await
+            .expect("Failed to fetch invoices incrementally");
+    }
+
+    println!("Delivered {} distinct invoices", delivered.len());
+
+    tokio::fs::remove_file(&path)
+        .await
+        .expect("Failed to remove state");
+}
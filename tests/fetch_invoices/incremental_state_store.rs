@@ -0,0 +1,50 @@
+use crate::common;
+use ksef_client::invoices::{FileStateStore, IncrementalFetchOptions, StateStore, SubjectType};
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_incremental_fetch_with_state_store() {
+    let client = common::authorize_client().await;
+
+    let path = std::env::temp_dir().join(format!("ksef-incremental-{}.json", std::process::id()));
+    let store = FileStateStore::new(&path);
+    let _ = tokio::fs::remove_file(&path).await;
+
+    let default_start = chrono::Utc::now() - chrono::Duration::days(1);
+    let options = IncrementalFetchOptions::new()
+        .with_window_end(chrono::Utc::now() + chrono::Duration::days(1));
+
+    let mut received = 0;
+    let state = client
+        .export_invoices_incrementally_with_store(
+            &store,
+            vec![SubjectType::Subject1],
+            default_start,
+            &options,
+            |subject_type, invoices| {
+                println!("{:?}: {} invoices", subject_type, invoices.len());
+                received += invoices.len();
+                Ok(())
+            },
+        )
+        .await
+        .expect("Failed to fetch invoices incrementally");
+
+    println!("Incrementally fetched {} invoices", received);
+
+    let saved = store
+        .load()
+        .await
+        .expect("Failed to load state")
+        .expect("State should be saved");
+    assert_eq!(saved, state);
+    assert!(
+        saved
+            .continuation_points
+            .contains_key(&SubjectType::Subject1)
+    );
+
+    tokio::fs::remove_file(&path)
+        .await
+        .expect("Failed to remove state");
+}
@@ -1 +1,10 @@
+pub mod archive;
+pub mod bulk_fetch;
+pub mod date_range_split;
+pub mod download_export;
 pub mod fetch_invoice_flow;
+pub mod incremental_seen_store;
+pub mod incremental_state_store;
+pub mod resumable_export;
+pub mod stream_invoice_metadata;
+pub mod verified_fetch;
@@ -0,0 +1,61 @@
+use crate::common;
+use ksef_client::invoices::{
+    DateRangeBuilder, DateType, ExportDownloadOptions, ExportHandle, QueryCriteriaBuilder,
+    SubjectType,
+};
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_resumable_export() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::PermanentStorage)
+        .from(now - chrono::Duration::days(7))
+        .to(now)
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let wrapping_key = ExportHandle::generate_wrapping_key().expect("Failed to generate key");
+    let handle = client
+        .start_resumable_export(query, &wrapping_key)
+        .await
+        .expect("Failed to start export");
+
+    let handle_path =
+        std::env::temp_dir().join(format!("ksef-export-handle-{}.json", std::process::id()));
+    handle
+        .save(&handle_path)
+        .await
+        .expect("Failed to save handle");
+
+    let dir = std::env::temp_dir().join(format!("ksef-resumed-export-{}", std::process::id()));
+    let download = client
+        .resume_export_to(
+            &handle_path,
+            &wrapping_key,
+            &dir,
+            &ExportDownloadOptions::default(),
+        )
+        .await
+        .expect("Failed to resume export");
+
+    println!("Resumed export with {} parts", download.parts.len());
+
+    let restored = ExportHandle::load(&handle_path)
+        .await
+        .expect("Failed to load handle");
+    assert_eq!(restored.reference_number, handle.reference_number);
+    assert!(restored.package_expiration_date.is_some());
+    assert!(!restored.is_expired());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    tokio::fs::remove_file(&handle_path).await.unwrap();
+}
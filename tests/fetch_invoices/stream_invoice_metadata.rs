@@ -0,0 +1,37 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::invoices::{DateRangeBuilder, DateType, QueryCriteriaBuilder, SubjectType};
+use ksef_client::prelude::*;
+use std::collections::HashSet;
+
+#[tokio::test]
+async fn test_stream_invoice_metadata() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Invoicing)
+        .from(now - chrono::Duration::days(30))
+        .to(now + chrono::Duration::hours(1))
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let mut stream = client.stream_invoice_metadata(query);
+    let mut ksef_numbers = HashSet::new();
+    while let Some(invoice) = stream.next().await {
+        let invoice = invoice.expect("Failed to stream invoice metadata");
+        assert!(
+            ksef_numbers.insert(invoice.ksef_number.clone()),
+            "Duplicate invoice {}",
+            invoice.ksef_number
+        );
+    }
+
+    println!("Streamed {} invoices", ksef_numbers.len());
+}
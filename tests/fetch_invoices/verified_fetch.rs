@@ -0,0 +1,48 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::invoices::{DateRangeBuilder, DateType, QueryCriteriaBuilder, SubjectType};
+use ksef_client::prelude::*;
+use ksef_client::types::InvoiceHash;
+
+#[tokio::test]
+async fn test_fetch_invoice_for_metadata() {
+    let client = common::authorize_client().await;
+
+    let now = chrono::Utc::now();
+    let date_range = DateRangeBuilder::new()
+        .date_type(DateType::Invoicing)
+        .from(now - chrono::Duration::days(30))
+        .to(now + chrono::Duration::hours(1))
+        .build()
+        .expect("Failed to build date_range");
+
+    let query = QueryCriteriaBuilder::new()
+        .subject_type(SubjectType::Subject1)
+        .date_range(date_range)
+        .build()
+        .expect("Failed to build query");
+
+    let metadata = match client.stream_invoice_metadata(query).next().await {
+        Some(metadata) => metadata.expect("Failed to fetch invoice metadata"),
+        None => {
+            println!("No invoices to verify");
+            return;
+        }
+    };
+
+    let invoice = client
+        .fetch_invoice_for_metadata(&metadata)
+        .await
+        .expect("Failed to fetch verified invoice");
+
+    assert_eq!(
+        invoice.hash,
+        InvoiceHash::new(metadata.invoice_hash.as_str())
+    );
+
+    let tampered = InvoiceHash::new("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=");
+    assert!(matches!(
+        invoice.verify_hash(&tampered),
+        Err(KsefError::IntegrityError(_))
+    ));
+}
@@ -62,7 +62,7 @@ async fn test_get_enrollment_status() {
             }
 
             assert!(
-                status_response.status.code >= 100,
+                status_response.status.code.code() >= 100,
                 "Status code should be valid (>= 100)"
             );
         }
@@ -59,7 +59,7 @@ async fn test_get_certificate_metadata_list() {
         Err(e) => panic!("Failed to get enrollment status: {:?}", e),
     };
 
-    if status_resp.status.code >= 400 {
+    if status_resp.status.code.code() >= 400 {
         println!("Enrollment was rejected. Skipping metadata list test.");
         if let Some(details) = status_resp.status.details {
             println!("Rejection details: {:?}", details);
@@ -57,7 +57,7 @@ async fn test_revoke_certificate() {
         Err(e) => panic!("Failed to get enrollment status: {:?}", e),
     };
 
-    if status_resp.status.code >= 400 {
+    if status_resp.status.code.code() >= 400 {
         println!("Enrollment was rejected. Skipping revocation test.");
         if let Some(details) = status_resp.status.details {
             println!("Rejection details: {:?}", details);
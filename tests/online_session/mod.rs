@@ -1 +1,5 @@
 pub mod online_session_flow;
+pub mod send_encrypted_invoice;
+pub mod send_invoices;
+pub mod session_status;
+pub mod watch_invoice_status;
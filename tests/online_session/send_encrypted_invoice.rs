@@ -0,0 +1,70 @@
+use crate::common;
+use ksef_client::certificates::PublicKeyCertificateUsage;
+use ksef_client::prelude::*;
+use ksef_client::sessions::{
+    OpenOnlineSessionRequestBuilder, SendInvoiceOptions, generate_encryption_data_from_certificate,
+    prepare_encrypted_invoice,
+};
+use ksef_client::types::ReferenceNumber;
+
+#[tokio::test]
+async fn test_send_encrypted_invoice() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let certificates = client
+        .get_public_key_certificates()
+        .await
+        .expect("Failed to get public key certificates");
+    let certificate = certificates
+        .iter()
+        .find(|c| {
+            c.usage
+                .contains(&PublicKeyCertificateUsage::SymmetricKeyEncryption)
+        })
+        .expect("No symmetric key encryption certificate");
+
+    let issuer_nip = "5261234567";
+    let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+
+    let encryption_data = generate_encryption_data_from_certificate(&certificate.certificate)
+        .expect("Failed to generate encryption data");
+    let encryption = encryption_data.to_encryption_info();
+    let encrypted_invoice = prepare_encrypted_invoice(invoice_xml.as_bytes(), &encryption_data)
+        .expect("Failed to encrypt invoice");
+    drop(encryption_data);
+
+    let request = OpenOnlineSessionRequestBuilder::new()
+        .with_encryption_info(encryption)
+        .build()
+        .expect("Failed to build OpenOnlineSessionRequest");
+
+    let response = client
+        .open_online_session(request)
+        .await
+        .expect("Failed to open online session");
+    let session_reference_number = ReferenceNumber::new(&response.reference_number);
+
+    let sent = client
+        .send_encrypted_invoice(
+            session_reference_number.clone(),
+            &encrypted_invoice,
+            &SendInvoiceOptions::default(),
+        )
+        .await
+        .expect("Failed to send encrypted invoice");
+
+    let status = client
+        .get_invoice_status(
+            session_reference_number.clone(),
+            ReferenceNumber::new(sent.reference_number),
+        )
+        .await
+        .expect("Failed to get invoice status");
+    assert!(status.invoice_status.code.is_success());
+    assert_eq!(status.invoice_hash, encrypted_invoice.invoice_hash.as_str());
+
+    client
+        .close_online_session(session_reference_number)
+        .await
+        .expect("Failed to close online session");
+}
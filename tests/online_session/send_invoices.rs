@@ -0,0 +1,62 @@
+use crate::common;
+use futures::StreamExt;
+use futures::stream;
+use ksef_client::prelude::*;
+use ksef_client::types::ReferenceNumber;
+
+use ksef_client::sessions::{OpenOnlineSessionRequestBuilder, SendInvoicesOptions, SentInvoice};
+
+#[tokio::test]
+async fn test_send_invoices_concurrently() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let encryption_data = client
+        .generate_encryption_data()
+        .await
+        .expect("Failed to generate encryption data");
+
+    let request = OpenOnlineSessionRequestBuilder::new()
+        .with_encryption(
+            &encryption_data.encrypted_symmetric_key,
+            &encryption_data.initialization_vector,
+        )
+        .build()
+        .expect("Failed to build OpenOnlineSessionRequest");
+
+    let response = client
+        .open_online_session(request)
+        .await
+        .expect("Failed to open online session");
+    let session_reference_number = ReferenceNumber::new(&response.reference_number);
+
+    let issuer_nip = "5261234567";
+    let mut invoices = Vec::new();
+    for _ in 0..5 {
+        invoices.push(common::generate_fa2_invoice(issuer_nip).await.into_bytes());
+    }
+
+    let results: Vec<SentInvoice> = client
+        .send_invoices(
+            session_reference_number.clone(),
+            stream::iter(invoices),
+            &encryption_data,
+            SendInvoicesOptions::new().with_concurrency(3),
+        )
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 5);
+    for (i, sent) in results.iter().enumerate() {
+        assert_eq!(sent.index, i, "Results should be returned in input order");
+        assert!(sent.invoice_hash.is_some());
+        match &sent.result {
+            Ok(reference_number) => assert!(!reference_number.is_empty()),
+            Err(e) => panic!("Invoice {} send failed: {:?}", i, e),
+        }
+    }
+
+    client
+        .close_online_session(session_reference_number)
+        .await
+        .expect("Failed to close online session");
+}
@@ -0,0 +1,55 @@
+use crate::common;
+use ksef_client::prelude::*;
+
+#[tokio::test]
+async fn test_session_status_and_invoices() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let issuer_nip = "5261234567";
+    let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+
+    let result = client
+        .submit_online(invoice_xml.as_bytes())
+        .await
+        .expect("Failed to submit online session");
+
+    client
+        .get_invoice_status(
+            result.session_reference_number.clone(),
+            result.invoice_reference_number.clone(),
+        )
+        .await
+        .expect("Failed to get invoice status");
+
+    let session_status = client
+        .get_session_status(result.session_reference_number.clone())
+        .await
+        .expect("Failed to get session status");
+
+    println!("Session status: {:#?}", session_status);
+
+    let invoices = client
+        .get_session_invoices(result.session_reference_number.clone(), Some(10), None)
+        .await
+        .expect("Failed to get session invoices");
+
+    assert_eq!(
+        invoices.invoices.len(),
+        1,
+        "Session should contain 1 invoice"
+    );
+    assert_eq!(
+        invoices.invoices[0].reference_number,
+        result.invoice_reference_number.as_str()
+    );
+
+    let failed = client
+        .get_failed_session_invoices(result.session_reference_number.clone(), None, None)
+        .await
+        .expect("Failed to get failed session invoices");
+
+    assert!(
+        failed.invoices.is_empty(),
+        "Session should not contain failed invoices"
+    );
+}
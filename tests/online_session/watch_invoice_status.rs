@@ -0,0 +1,93 @@
+use crate::common;
+use futures::StreamExt;
+use ksef_client::prelude::*;
+use ksef_client::types::ReferenceNumber;
+
+use ksef_client::sessions::{OpenOnlineSessionRequestBuilder, WatchStatusOptions};
+
+#[tokio::test]
+async fn test_watch_invoice_statuses() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let encryption_data = client
+        .generate_encryption_data()
+        .await
+        .expect("Failed to generate encryption data");
+
+    let request = OpenOnlineSessionRequestBuilder::new()
+        .with_encryption(
+            &encryption_data.encrypted_symmetric_key,
+            &encryption_data.initialization_vector,
+        )
+        .build()
+        .expect("Failed to build OpenOnlineSessionRequest");
+
+    let response = client
+        .open_online_session(request)
+        .await
+        .expect("Failed to open online session");
+    let session_reference_number = ReferenceNumber::new(&response.reference_number);
+
+    let issuer_nip = "5261234567";
+    let mut invoice_reference_numbers = Vec::new();
+    for _ in 0..3 {
+        let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+        let sent = client
+            .send_invoice(
+                session_reference_number.clone(),
+                invoice_xml.as_bytes(),
+                &encryption_data,
+            )
+            .await
+            .expect("Failed to send invoice");
+        invoice_reference_numbers.push(ReferenceNumber::new(sent.reference_number));
+    }
+
+    let first = client
+        .get_invoice_status_once(
+            session_reference_number.clone(),
+            invoice_reference_numbers[0].clone(),
+        )
+        .await
+        .expect("Failed to get invoice status");
+    println!("First status: {:?}", first.invoice_status);
+
+    let last = client
+        .watch_invoice_status(
+            session_reference_number.clone(),
+            invoice_reference_numbers[0].clone(),
+            WatchStatusOptions::default(),
+        )
+        .collect::<Vec<_>>()
+        .await
+        .pop()
+        .expect("Watch stream should yield at least one status")
+        .expect("Failed to watch invoice status");
+    assert!(last.invoice_status.code.is_success());
+
+    let updates = client
+        .watch_invoice_statuses(
+            session_reference_number.clone(),
+            invoice_reference_numbers.clone(),
+            WatchStatusOptions::default(),
+        )
+        .collect::<Vec<_>>()
+        .await;
+
+    for reference_number in &invoice_reference_numbers {
+        let final_status = updates
+            .iter()
+            .rfind(|u| &u.invoice_reference_number == reference_number)
+            .expect("Every invoice should have a status update");
+        let status = final_status
+            .result
+            .as_ref()
+            .expect("Failed to watch invoice status");
+        assert!(status.invoice_status.code.is_success());
+    }
+
+    client
+        .close_online_session(session_reference_number)
+        .await
+        .expect("Failed to close online session");
+}
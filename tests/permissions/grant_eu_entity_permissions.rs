@@ -140,7 +140,7 @@ async fn test_grant_eu_entity_permissions_person_without_id() {
             person_by_fp_no_id: Some(EuEntityPersonByFpNoId {
                 first_name: "Anna".to_string(),
                 last_name: "Nowak".to_string(),
-                birth_date: "1990-01-01".to_string(),
+                birth_date: chrono::NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
                 id_document: EuEntityIdDocument {
                     document_type: "PASSPORT".to_string(),
                     number: "ABC123456".to_string(),
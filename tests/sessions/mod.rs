@@ -1,4 +1,5 @@
 pub mod active_sessions;
+pub mod query_sessions;
 pub mod revoke_current_session;
 pub mod revoke_other_session;
 pub mod sessions_lifecycle;
@@ -0,0 +1,49 @@
+use crate::common;
+use chrono::{Duration, Utc};
+use ksef_client::prelude::*;
+use ksef_client::sessions::{QueryInvoiceSessionsRequest, SessionType};
+
+#[tokio::test]
+async fn test_query_online_sessions() {
+    let client: ksef_client::KsefClient = common::authorize_client().await;
+
+    let issuer_nip = "5261234567";
+    let invoice_xml: String = common::generate_fa2_invoice(issuer_nip).await;
+
+    let result = client
+        .submit_online(invoice_xml.as_bytes())
+        .await
+        .expect("Failed to submit online session");
+
+    let request = QueryInvoiceSessionsRequest::builder()
+        .session_type(SessionType::Online)
+        .date_created_from(Utc::now() - Duration::hours(1))
+        .build()
+        .expect("Failed to build QueryInvoiceSessionsRequest");
+
+    let mut found = false;
+    let mut continuation_token: Option<String> = None;
+
+    loop {
+        let page = client
+            .query_sessions(request.clone(), Some(100), continuation_token.as_deref())
+            .await
+            .expect("Failed to query sessions");
+
+        if page
+            .sessions
+            .iter()
+            .any(|s| s.reference_number == result.session_reference_number.as_str())
+        {
+            found = true;
+            break;
+        }
+
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    assert!(found, "Submitted session should be returned by the query");
+}
@@ -101,7 +101,7 @@ async fn test_revoke_session_by_reference() {
     if let Some(s) = found {
         println!("Found session {} with status code {}", ref_b, s.status.code);
         assert!(
-            s.status.code == 425 || s.status.code >= 400,
+            s.status.code == 425 || s.status.code.code() >= 400,
             "Expected revoked or non-active status for the revoked session"
         );
     } else {
@@ -149,7 +149,7 @@ async fn test_two_session_lifecycle_revoke_other_and_self() {
             ref2, s.status.code
         );
         assert!(
-            s.status.code == 425 || s.status.code >= 400,
+            s.status.code == 425 || s.status.code.code() >= 400,
             "Expected client2 session to be revoked after client1 revoked it"
         );
     } else {
@@ -182,7 +182,7 @@ async fn test_two_session_lifecycle_revoke_other_and_self() {
     if let Some(s) = final_sess2 {
         println!("Final check: session {} status {}", ref2, s.status.code);
         assert!(
-            s.status.code == 425 || s.status.code >= 400,
+            s.status.code == 425 || s.status.code.code() >= 400,
             "Expected client2 session to be revoked in final check"
         );
     } else {
@@ -209,7 +209,7 @@ async fn test_two_session_lifecycle_revoke_other_and_self() {
     if let Some(s) = end_sess1 {
         println!("End check: session {} status {}", ref1, s.status.code);
         assert!(
-            s.status.code == 425 || s.status.code >= 400,
+            s.status.code == 425 || s.status.code.code() >= 400,
             "Expected client1 session to be revoked in end check"
         );
     } else {
@@ -1,2 +1,3 @@
 pub mod invoice_upo_by_ksef_number;
 pub mod invoice_upo_by_reference_number;
+pub mod session_upo;
@@ -0,0 +1,55 @@
+use crate::common;
+use ksef_client::prelude::*;
+
+use std::time::Duration;
+
+#[tokio::test]
+async fn integration_session_upo_download() {
+    let client: KsefClient = common::authorize_client().await;
+    let seller_nip = client.context.value.clone();
+
+    let invoice_xml: String = common::generate_fa2_invoice(&seller_nip).await;
+    let submit_result = client
+        .submit_online(invoice_xml.as_bytes())
+        .await
+        .expect("Failed to submit invoice");
+
+    let mut session_status = client
+        .get_session_status(submit_result.session_reference_number.clone())
+        .await
+        .expect("Failed to get session status");
+
+    for _ in 0..30 {
+        if session_status.upo.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        session_status = client
+            .get_session_status(submit_result.session_reference_number.clone())
+            .await
+            .expect("Failed to get session status");
+    }
+
+    let upo = session_status
+        .upo
+        .expect("Session UPO should be available after close");
+    assert!(!upo.pages.is_empty(), "UPO should have at least one page");
+
+    let dir = std::env::temp_dir().join(format!(
+        "ksef-session-upo-{}",
+        submit_result.session_reference_number
+    ));
+
+    let saved = client
+        .download_session_upo(submit_result.session_reference_number.clone(), &dir)
+        .await
+        .expect("Failed to download session UPO");
+
+    assert_eq!(saved.len(), upo.pages.len());
+    for page in &saved {
+        assert!(page.path.exists(), "UPO page file should exist");
+        assert!(!page.hash.is_empty(), "UPO page hash should not be empty");
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}